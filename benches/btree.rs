@@ -0,0 +1,88 @@
+use algorithm::{ArenaBTree, BTree};
+use std::collections::BTreeSet;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const N: usize = 200_000;
+
+/// xorshift64, so that every run inserts the same values.
+fn random_values(n: usize) -> Vec<u64> {
+    let mut x = 88172645463325252u64;
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        })
+        .collect()
+}
+
+/// million operations per second.
+fn throughput(elapsed: Duration) -> f64 {
+    N as f64 / elapsed.as_secs_f64() / 1e6
+}
+
+fn report(name: &str, insert: Duration, lookup: Duration) {
+    println!("{name:<20} {:>12.2} {:>12.2}", throughput(insert), throughput(lookup));
+}
+
+fn bench_btree<const B: usize>(values: &[u64], queries: &[u64]) {
+    let start = Instant::now();
+    let mut tree = BTree::<u64, B>::new();
+    for &value in values {
+        tree.insert(value);
+    }
+    let insert = start.elapsed();
+    let start = Instant::now();
+    let found = queries.iter().filter(|x| tree.find(black_box(x))).count();
+    let lookup = start.elapsed();
+    black_box(found);
+    report(&format!("BTree<_, {B}>"), insert, lookup);
+}
+
+fn bench_arena<const B: usize>(values: &[u64], queries: &[u64]) {
+    let start = Instant::now();
+    let mut tree = ArenaBTree::<u64, B>::with_capacity(values.len());
+    for &value in values {
+        tree.insert(value);
+    }
+    let insert = start.elapsed();
+    let start = Instant::now();
+    let found = queries.iter().filter(|x| tree.find(black_box(x))).count();
+    let lookup = start.elapsed();
+    black_box(found);
+    report(&format!("ArenaBTree<_, {B}>"), insert, lookup);
+}
+
+fn bench_std(values: &[u64], queries: &[u64]) {
+    let start = Instant::now();
+    let mut set = BTreeSet::new();
+    for &value in values {
+        set.insert(value);
+    }
+    let insert = start.elapsed();
+    let start = Instant::now();
+    let found = queries.iter().filter(|x| set.contains(black_box(x))).count();
+    let lookup = start.elapsed();
+    black_box(found);
+    report("BTreeSet", insert, lookup);
+}
+
+fn main() {
+    let values = random_values(N);
+    // 半分は木に入っている値, 半分は入っていない値を探す.
+    let mut queries: Vec<u64> = values.iter().step_by(2).copied().collect();
+    queries.extend(random_values(N + N / 2).into_iter().skip(N));
+    println!("{N} random u64 values, million operations per second");
+    println!("{:<20} {:>12} {:>12}", "", "insert", "lookup");
+    bench_btree::<4>(&values, &queries);
+    bench_btree::<6>(&values, &queries);
+    bench_btree::<8>(&values, &queries);
+    bench_btree::<16>(&values, &queries);
+    bench_btree::<32>(&values, &queries);
+    bench_btree::<64>(&values, &queries);
+    bench_arena::<4>(&values, &queries);
+    bench_arena::<16>(&values, &queries);
+    bench_std(&values, &queries);
+}
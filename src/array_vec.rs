@@ -0,0 +1,149 @@
+use std::{
+    fmt::Debug,
+    ops::{Index, IndexMut},
+};
+
+///
+/// a vector of at most N values stored inline, used for the values and children of a tree node.
+/// the slots at or beyond len are always None, so shifting values never copies or forgets one.
+///
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct ArrayVec<T, const N: usize> {
+    items: [Option<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    pub(crate) fn new() -> Self {
+        Self {
+            items: std::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub(crate) fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    ///
+    /// add value at the end.
+    /// # Panics
+    /// panics if self already has N values.
+    ///
+    pub(crate) fn push(&mut self, value: T) {
+        assert!(self.len < N, "ArrayVec is full (capacity {N})");
+        self.items[self.len] = Some(value);
+        self.len += 1;
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.items[self.len].take()
+    }
+
+    ///
+    /// insert value at index, shifting the values after it to the right.
+    /// # Panics
+    /// panics if index > len or self already has N values.
+    ///
+    pub(crate) fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "insertion index (is {index}) should be <= len (is {})", self.len);
+        self.push(value);
+        // 末尾に置いてから回転させて, 空きスロットだけを動かす.
+        self.items[index..self.len].rotate_right(1);
+    }
+
+    ///
+    /// remove the value at index, shifting the values after it to the left.
+    /// # Panics
+    /// panics if index >= len.
+    ///
+    pub(crate) fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "removal index (is {index}) should be < len (is {})", self.len);
+        self.items[index..self.len].rotate_left(1);
+        self.pop().unwrap()
+    }
+
+    ///
+    /// move the values from at onwards to a new ArrayVec.
+    /// # Panics
+    /// panics if at > len.
+    ///
+    pub(crate) fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "split index (is {at}) should be <= len (is {})", self.len);
+        let mut other = Self::new();
+        for slot in &mut self.items[at..self.len] {
+            other.push(slot.take().unwrap());
+        }
+        self.len = at;
+        other
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items[..self.len].iter().map(|x| x.as_ref().unwrap())
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for ArrayVec<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        assert!(index < self.len, "index (is {index}) should be < len (is {})", self.len);
+        self.items[index].as_ref().unwrap()
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for ArrayVec<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        assert!(index < self.len, "index (is {index}) should be < len (is {})", self.len);
+        self.items[index].as_mut().unwrap()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for ArrayVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for ArrayVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut array = Self::new();
+        array.extend(iter);
+        array
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = std::iter::Flatten<std::array::IntoIter<Option<T>, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter().flatten()
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -1,8 +1,7 @@
 /**
  * bit search allows you to search from 000000...0, 00000...1, ..., 11111...1
  */
-
- pub struct BitSearcherResult {
+pub struct BitSearcherResult {
     n: usize
 }
 
@@ -24,7 +23,7 @@ impl BitSearcherResult {
         bits
     }
 
-    pub fn cover<T: Clone>(&self, target: &Vec<T>) -> Vec<T> {
+    pub fn cover<T: Clone>(&self, target: &[T]) -> Vec<T> {
         if target.len() > 63 {
             panic!("size must be less than or equal to 63, we got {}", target.len());
         }
@@ -43,6 +42,12 @@ impl BitSearcher {
     }
 }
 
+impl Default for BitSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Iterator for BitSearcher {
     type Item = BitSearcherResult;
 
@@ -1,7 +1,7 @@
 use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, VecDeque};
 
-pub fn dijkstra(graph: &Vec<Vec<(usize, usize)>>, start: usize) -> Vec<usize> {
+pub fn dijkstra(graph: &[Vec<(usize, usize)>], start: usize) -> Vec<usize> {
     let n = graph.len();
     let mut dist = vec![usize::MAX; n];
 
@@ -26,4 +26,37 @@ pub fn dijkstra(graph: &Vec<Vec<(usize, usize)>>, start: usize) -> Vec<usize> {
     }
 
     dist
-}
\ No newline at end of file
+}
+
+///
+/// return a shortest path tree from start, given dist computed by [`dijkstra`] from start.
+/// for each vertex reachable from start except start itself, the result holds (u, i),
+/// where the i-th edge of graph[u] is the last edge of a shortest path to the vertex.
+/// the tree is built by a search from start over the edges on shortest paths,
+/// so it has no cycles even when some edges cost 0.
+///
+/// # Example
+/// ```
+/// use algorithm::{dijkstra, shortest_path_tree};
+/// let graph = vec![vec![(1, 4), (2, 1)], vec![], vec![(1, 2)]];
+/// let dist = dijkstra(&graph, 0);
+/// assert_eq!(shortest_path_tree(&graph, 0, &dist), vec![None, Some((2, 0)), Some((0, 1))]);
+/// ```
+///
+pub fn shortest_path_tree(graph: &[Vec<(usize, usize)>], start: usize, dist: &[usize]) -> Vec<Option<(usize, usize)>> {
+    let mut parent = vec![None; graph.len()];
+    let mut visited = vec![false; graph.len()];
+    visited[start] = true;
+    let mut queue = VecDeque::from([start]);
+    while let Some(u) = queue.pop_front() {
+        for (i, &(v, c)) in graph[u].iter().enumerate() {
+            // 最短路に乗っている辺だけをたどる.
+            if !visited[v] && dist[u].checked_add(c) == Some(dist[v]) {
+                visited[v] = true;
+                parent[v] = Some((u, i));
+                queue.push_back(v);
+            }
+        }
+    }
+    parent
+}
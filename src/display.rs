@@ -0,0 +1,125 @@
+use crate::{AugmentedRBTree, BTree, Monoid};
+use std::fmt::{Display, Formatter, Result};
+
+///
+/// draw the tree for humans: `{}` prints one line per level, where the children of each parent stay together
+/// and the groups of different parents are separated by ` | `,
+/// and `{:#}` prints one node per line under its parent with connectors, which stays readable for deep trees.
+/// each node is drawn as a box `[1|2|3]` of its values, and an empty tree as `[]`.
+///
+/// # Example
+/// ```
+/// use algorithm::Tree234;
+/// let tree = Tree234::from(vec![1, 2, 3, 4, 5, 6]);
+/// assert_eq!(format!("{tree}"), "[4]\n[1|2|3] [5|6]");
+/// assert_eq!(format!("{tree:#}"), "[4]\n├── [1|2|3]\n└── [5|6]");
+/// let tree: Tree234<i32> = "[[[1] 2 [3]] 4 [[5] 6 [7 8]]]".parse().unwrap();
+/// assert_eq!(format!("{tree}"), "[4]\n[2] [6]\n[1] [3] | [5] [7|8]");
+/// ```
+///
+impl<T: Display, const B: usize, M: Monoid<T>> Display for BTree<T, B, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if f.alternate() {
+            let mut lines = vec![];
+            draw(self, &mut lines, String::new(), String::new(), &node_box, &btree_children);
+            return write!(f, "{}", lines.join("\n"));
+        }
+        write!(f, "{}", levels(self, &node_box, &btree_children))
+    }
+}
+
+fn node_box<T: Display, const B: usize, M: Monoid<T>>(node: &BTree<T, B, M>) -> String {
+    let values: Vec<_> = node.data.iter().map(|value| value.to_string()).collect();
+    format!("[{}]", values.join("|"))
+}
+
+fn btree_children<T, const B: usize, M: Monoid<T>>(node: &BTree<T, B, M>) -> Vec<Option<&BTree<T, B, M>>> {
+    node.children.iter().map(|child| Some(child.as_ref())).collect()
+}
+
+///
+/// draw the tree for humans with `R:` or `B:` before each value for its colour:
+/// `{}` prints one line per level with the two children of each parent together and the parents separated by ` | `,
+/// and `{:#}` prints one node per line under its parent with connectors.
+/// the left child comes first, and a missing child next to a present one is drawn as `·`.
+/// an empty tree is drawn as `·`.
+///
+/// # Example
+/// ```
+/// use algorithm::RBTree;
+/// let tree = RBTree::from(vec![2, 1, 3, 4]);
+/// assert_eq!(format!("{tree}"), "B:2\nB:1 B:3\n· R:4");
+/// assert_eq!(format!("{tree:#}"), "B:2\n├── B:1\n└── B:3\n    ├── ·\n    └── R:4");
+/// ```
+///
+impl<T: Display, M: Monoid<T>> Display for AugmentedRBTree<T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.empty() {
+            return write!(f, "·");
+        }
+        if f.alternate() {
+            let mut lines = vec![];
+            draw(self, &mut lines, String::new(), String::new(), &colored, &rb_children);
+            return write!(f, "{}", lines.join("\n"));
+        }
+        write!(f, "{}", levels(self, &colored, &rb_children))
+    }
+}
+
+fn colored<T: Display, M: Monoid<T>>(node: &AugmentedRBTree<T, M>) -> String {
+    let color = if node.is_red() { 'R' } else { 'B' };
+    format!("{color}:{}", node.data())
+}
+
+///
+/// the children of node from the left, with None for Nil. a leaf has none, so that nothing is drawn below it.
+///
+fn rb_children<T, M: Monoid<T>>(node: &AugmentedRBTree<T, M>) -> Vec<Option<&AugmentedRBTree<T, M>>> {
+    if node.left().empty() && node.right().empty() {
+        return vec![];
+    }
+    [node.left(), node.right()].into_iter().map(|child| (!child.empty()).then_some(child)).collect()
+}
+
+///
+/// the lines of the level view of the tree rooted at root: the children of each parent, from children,
+/// are joined by a space, and the groups of the parents on the same level by ` | `.
+/// a None child is drawn as `·`.
+///
+fn levels<N>(root: &N, label: &impl Fn(&N) -> String, children: &impl Fn(&N) -> Vec<Option<&N>>) -> String {
+    let mut level = vec![vec![Some(root)]];
+    let mut lines = vec![];
+    while !level.is_empty() {
+        let groups: Vec<_> = level
+            .iter()
+            .map(|group| group.iter().map(|node| node.map_or("·".to_string(), label)).collect::<Vec<_>>().join(" "))
+            .collect();
+        lines.push(groups.join(" | "));
+        level = level.iter().flatten().flatten().map(|node| children(node)).filter(|group| !group.is_empty()).collect();
+    }
+    lines.join("\n")
+}
+
+///
+/// push the lines drawing the subtree rooted at node, like the `tree` command.
+/// head is put before the node itself, and indent before the lines of its descendants.
+/// a None child is drawn as `·`.
+///
+fn draw<N>(
+    node: &N,
+    lines: &mut Vec<String>,
+    head: String,
+    indent: String,
+    label: &impl Fn(&N) -> String,
+    children: &impl Fn(&N) -> Vec<Option<&N>>,
+) {
+    lines.push(format!("{head}{}", label(node)));
+    let kids = children(node);
+    for (i, child) in kids.iter().enumerate() {
+        let (branch, next) = if i + 1 == kids.len() { ("└── ", "    ") } else { ("├── ", "│   ") };
+        match child {
+            Some(child) => draw(*child, lines, format!("{indent}{branch}"), format!("{indent}{next}"), label, children),
+            None => lines.push(format!("{indent}{branch}·")),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{dijkstra, shortest_path_tree, AugmentedRBTree, BTree, Monoid, UnionFind};
+use std::fmt::{Display, Write};
+
+///
+/// escape the characters which have a meaning inside a label of Graphviz, including those of record shapes.
+///
+fn escape(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        if matches!(c, '"' | '\\' | '{' | '}' | '|' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl<T: Display, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// render the tree in the DOT language of Graphviz.
+    /// each node is a record whose fields are the values, with a port between them for each child.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![1, 2, 3, 4]);
+    /// let dot = tree.to_dot();
+    /// assert!(dot.starts_with("digraph BTree {"));
+    /// assert!(dot.contains("n0:c1 -> n2;"));
+    /// ```
+    ///
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph BTree {\n    node [shape=record];\n");
+        if !self.is_empty() {
+            self.write_dot(&mut dot, &mut 0);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    ///
+    /// write the subtree rooted at self with ids from *next on, and return the id of self.
+    ///
+    fn write_dot(&self, dot: &mut String, next: &mut usize) -> usize {
+        let id = *next;
+        *next += 1;
+        let mut fields = vec![];
+        for (i, value) in self.data.iter().enumerate() {
+            if !self.is_leaf() {
+                fields.push(format!("<c{i}>"));
+            }
+            fields.push(escape(&value.to_string()));
+        }
+        if !self.is_leaf() {
+            fields.push(format!("<c{}>", self.data.len()));
+        }
+        writeln!(dot, "    n{id} [label=\"{}\"];", fields.join("|")).unwrap();
+        for (i, child) in self.children.iter().enumerate() {
+            let child_id = child.write_dot(dot, next);
+            writeln!(dot, "    n{id}:c{i} -> n{child_id};").unwrap();
+        }
+        id
+    }
+}
+
+impl<T: Display, M: Monoid<T>> AugmentedRBTree<T, M> {
+    ///
+    /// render the tree in the DOT language of Graphviz, with red and black nodes filled in their colours.
+    /// a missing child is drawn as an invisible node so that a lone child keeps its side.
+    /// ```rust
+    /// use algorithm::RBTree;
+    /// let tree = RBTree::from(vec![2, 1]);
+    /// let dot = tree.to_dot();
+    /// assert!(dot.contains("n0 [label=\"1\", fillcolor=black];"));
+    /// assert!(dot.contains("n2 [label=\"2\", fillcolor=red];"));
+    /// assert!(dot.contains("n1 [style=invis];"));
+    /// ```
+    ///
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph RBTree {\n    node [shape=circle, style=filled, fontcolor=white];\n");
+        if !self.empty() {
+            self.write_dot(&mut dot, &mut 0);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String, next: &mut usize) -> usize {
+        let id = *next;
+        *next += 1;
+        let color = if self.is_red() { "red" } else { "black" };
+        writeln!(dot, "    n{id} [label=\"{}\", fillcolor={color}];", escape(&self.data().to_string())).unwrap();
+        if self.left().empty() && self.right().empty() {
+            return id;
+        }
+        for child in [self.left(), self.right()] {
+            let child_id = if child.empty() {
+                let nil = *next;
+                *next += 1;
+                writeln!(dot, "    n{nil} [style=invis];").unwrap();
+                nil
+            } else {
+                child.write_dot(dot, next)
+            };
+            let style = if child.empty() { " [style=invis]" } else { "" };
+            writeln!(dot, "    n{id} -> n{child_id}{style};").unwrap();
+        }
+        id
+    }
+}
+
+impl UnionFind {
+    ///
+    /// render the parent forest in the DOT language of Graphviz, with an edge from each element to its parent.
+    /// roots are drawn as double circles. the forest is drawn as it is, without compressing paths.
+    /// ```rust
+    /// use algorithm::UnionFind;
+    /// let mut uf = UnionFind::new(3);
+    /// uf.unite(0, 1);
+    /// let dot = uf.to_dot();
+    /// assert!(dot.contains("0 -> 1;"));
+    /// assert!(dot.contains("1 [shape=doublecircle];"));
+    /// ```
+    ///
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph UnionFind {\n    node [shape=circle];\n");
+        for (x, &parent) in self.parent.iter().enumerate() {
+            if parent == x {
+                writeln!(dot, "    {x} [shape=doublecircle];").unwrap();
+            } else {
+                writeln!(dot, "    {x} -> {parent};").unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+///
+/// render a directed graph given as adjacency lists of (to, cost), as taken by [`dijkstra`],
+/// in the DOT language of Graphviz. each edge is labelled with its cost.
+/// if start is given, each vertex is labelled with its distance from start,
+/// and the edges of the shortest path tree from [`shortest_path_tree`] are drawn in bold red.
+///
+/// # Example
+/// ```
+/// use algorithm::graph_to_dot;
+/// let graph = vec![vec![(1, 4), (2, 1)], vec![], vec![(1, 2)]];
+/// let dot = graph_to_dot(&graph, Some(0));
+/// assert!(dot.contains("0 -> 2 [label=\"1\", color=red, penwidth=2];"));
+/// assert!(dot.contains("0 -> 1 [label=\"4\"];"));
+/// assert!(dot.contains("1 [label=\"1\\n3\"];"));
+/// ```
+///
+pub fn graph_to_dot(graph: &[Vec<(usize, usize)>], start: Option<usize>) -> String {
+    let mut dot = String::from("digraph Graph {\n    node [shape=circle];\n");
+    let dist = start.map(|start| dijkstra(graph, start));
+    let parent = start.zip(dist.as_ref()).map(|(start, dist)| shortest_path_tree(graph, start, dist));
+    for u in 0..graph.len() {
+        match dist.as_ref().map(|dist| dist[u]) {
+            Some(usize::MAX) => writeln!(dot, "    {u} [label=\"{u}\\n∞\"];").unwrap(),
+            Some(d) => writeln!(dot, "    {u} [label=\"{u}\\n{d}\"];").unwrap(),
+            None => writeln!(dot, "    {u};").unwrap(),
+        }
+    }
+    for (u, edges) in graph.iter().enumerate() {
+        for (i, &(v, cost)) in edges.iter().enumerate() {
+            let in_tree = parent.as_ref().is_some_and(|parent| parent[v] == Some((u, i)));
+            let highlight = if in_tree { ", color=red, penwidth=2" } else { "" };
+            writeln!(dot, "    {u} -> {v} [label=\"{cost}\"{highlight}];").unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
@@ -0,0 +1,218 @@
+use crate::{ordered_set::below, AugmentedRBTree, Monoid};
+use std::{
+    fmt::Debug,
+    ops::{Bound, Range},
+};
+
+///
+/// the largest end of the intervals in a subtree. None for an empty subtree.
+///
+struct MaxEnd;
+
+impl<K: Ord + Clone> Monoid<(K, K)> for MaxEnd {
+    type Value = Option<K>;
+    fn identity() -> Option<K> {
+        None
+    }
+    fn single(value: &(K, K)) -> Option<K> {
+        Some(value.1.clone())
+    }
+    fn op(a: &Option<K>, b: &Option<K>) -> Option<K> {
+        a.clone().max(b.clone())
+    }
+}
+
+///
+/// a set of half-open intervals `start..end` kept in a red-black tree ordered by (start, end),
+/// where each subtree caches the largest end below it.
+/// an interval overlapping a query lies in a subtree only if the cached end is beyond the query start,
+/// so `overlapping` and `stabbing` skip the other subtrees and take O((k + 1) log n) for k results.
+/// like [`RBTree`](crate::RBTree), equal intervals may be inserted several times.
+///
+/// # Example
+/// ```
+/// use algorithm::IntervalTree;
+/// let mut tree = IntervalTree::new();
+/// tree.insert(1..5);
+/// tree.insert(3..8);
+/// tree.insert(6..7);
+/// assert_eq!(tree.stabbing(4).collect::<Vec<_>>(), vec![1..5, 3..8]);
+/// assert_eq!(tree.overlapping(5..7).collect::<Vec<_>>(), vec![3..8, 6..7]);
+/// assert!(tree.remove(&(3..8)));
+/// assert_eq!(tree.stabbing(6).collect::<Vec<_>>(), vec![6..7]);
+/// ```
+///
+pub struct IntervalTree<K: Ord + Clone> {
+    tree: AugmentedRBTree<(K, K), MaxEnd>,
+}
+
+impl<K: Ord + Clone> Default for IntervalTree<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone> Clone for IntervalTree<K> {
+    fn clone(&self) -> Self {
+        Self { tree: self.tree.clone() }
+    }
+}
+
+impl<K: Ord + Clone + Debug> Debug for IntervalTree<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord + Clone> From<Vec<Range<K>>> for IntervalTree<K> {
+    ///
+    /// make an IntervalTree from a Vec of intervals in O(n log n).
+    ///
+    fn from(v: Vec<Range<K>>) -> Self {
+        let mut tree = Self::new();
+        for interval in v {
+            tree.insert(interval);
+        }
+        tree
+    }
+}
+
+impl<K: Ord + Clone> IntervalTree<K> {
+    pub fn new() -> Self {
+        Self { tree: AugmentedRBTree::new() }
+    }
+
+    ///
+    /// clear the tree.
+    ///
+    pub fn clear(&mut self) {
+        self.tree.clear();
+    }
+
+    ///
+    /// check if the tree is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    ///
+    /// return the number of intervals in O(1).
+    ///
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    ///
+    /// insert interval in O(log n).
+    /// panics if interval.start > interval.end. an empty interval is kept but overlaps nothing.
+    ///
+    pub fn insert(&mut self, interval: Range<K>) {
+        assert!(interval.start <= interval.end, "the start of an interval should not exceed its end");
+        self.tree.insert((interval.start, interval.end));
+    }
+
+    ///
+    /// if interval is in self, remove one occurrence of it and return true. else return false
+    ///
+    pub fn remove(&mut self, interval: &Range<K>) -> bool {
+        self.tree.delete(&(interval.start.clone(), interval.end.clone()))
+    }
+
+    ///
+    /// check if self contains interval.
+    ///
+    pub fn contains(&self, interval: &Range<K>) -> bool {
+        self.tree.contains(&(interval.start.clone(), interval.end.clone()))
+    }
+
+    ///
+    /// iterate over the intervals in ascending order of (start, end).
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = Range<K>> + '_ {
+        self.tree.iter().map(|(start, end)| start.clone()..end.clone())
+    }
+
+    ///
+    /// iterate over the intervals sharing at least one point with range, in ascending order of (start, end).
+    /// ```rust
+    /// use algorithm::IntervalTree;
+    /// let tree = IntervalTree::from(vec![0..2, 2..4, 4..6]);
+    /// // 半開区間なので端点が接するだけでは重ならない.
+    /// assert_eq!(tree.overlapping(2..4).collect::<Vec<_>>(), vec![2..4]);
+    /// assert_eq!(tree.overlapping(1..5).count(), 3);
+    /// assert_eq!(tree.overlapping(3..3).count(), 0);
+    /// ```
+    ///
+    pub fn overlapping(&self, range: Range<K>) -> Overlapping<'_, K> {
+        if range.start >= range.end {
+            return Overlapping { stack: vec![], low: range.start, high: Bound::Excluded(range.end) };
+        }
+        Overlapping::new(&self.tree, range.start, Bound::Excluded(range.end))
+    }
+
+    ///
+    /// iterate over the intervals containing point, in ascending order of (start, end).
+    ///
+    pub fn stabbing(&self, point: K) -> Overlapping<'_, K> {
+        Overlapping::new(&self.tree, point.clone(), Bound::Included(point))
+    }
+
+    ///
+    /// return the largest end of the intervals in O(1). None if the tree is empty.
+    ///
+    pub fn max_end(&self) -> Option<K> {
+        self.tree.aggregate()
+    }
+}
+
+///
+/// an iterator over the intervals which end after low and start before high.
+/// created by [`IntervalTree::overlapping`] and [`IntervalTree::stabbing`].
+///
+pub struct Overlapping<'a, K: Ord + Clone> {
+    ///
+    /// the nodes whose own interval and right subtree are still to be visited, the next one on the top.
+    ///
+    stack: Vec<&'a AugmentedRBTree<(K, K), MaxEnd>>,
+    low: K,
+    high: Bound<K>,
+}
+
+impl<'a, K: Ord + Clone> Overlapping<'a, K> {
+    fn new(tree: &'a AugmentedRBTree<(K, K), MaxEnd>, low: K, high: Bound<K>) -> Self {
+        let mut iter = Self { stack: vec![], low, high };
+        iter.push_leftmost(tree);
+        iter
+    }
+
+    ///
+    /// push the left spine of node, stopping at a subtree whose intervals all end by low.
+    ///
+    fn push_leftmost(&mut self, mut node: &'a AugmentedRBTree<(K, K), MaxEnd>) {
+        while node.aggregate().is_some_and(|end| end > self.low) {
+            self.stack.push(node);
+            node = node.left();
+        }
+    }
+}
+
+impl<'a, K: Ord + Clone> Iterator for Overlapping<'a, K> {
+    type Item = Range<K>;
+
+    fn next(&mut self) -> Option<Range<K>> {
+        while let Some(node) = self.stack.pop() {
+            let (start, end) = node.data();
+            if !below(start, self.high.as_ref()) {
+                // これ以降の区間はすべて始まりが遅いので, 探索を打ち切る.
+                self.stack.clear();
+                return None;
+            }
+            self.push_leftmost(node.right());
+            if end > &self.low && start < end {
+                return Some(start.clone()..end.clone());
+            }
+        }
+        None
+    }
+}
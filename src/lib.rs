@@ -1,11 +1,45 @@
+#![forbid(unsafe_code)]
+
 mod dijkstra;
 pub use dijkstra::*;
 mod union_find;
 pub use union_find::*;
 mod bit_search;
 pub use bit_search::*;
+mod array_vec;
 mod tree234;
 pub use tree234::*;
+mod tree234_by;
+pub use tree234_by::*;
+mod tree234_set;
+pub use tree234_set::*;
+mod tree234_validate;
+pub use tree234_validate::*;
+mod tree234_cursor;
+pub use tree234_cursor::*;
+mod tree234_monoid;
+pub use tree234_monoid::*;
+mod tree234_arena;
+pub use tree234_arena::*;
+mod tree234_persistent;
+pub use tree234_persistent::*;
+mod tree234_trace;
+pub use tree234_trace::*;
+mod tree234_shape;
+pub use tree234_shape::*;
+mod tree234_link;
+pub use tree234_link::*;
 mod tests;
 mod rbtree;
-pub use rbtree::*;
\ No newline at end of file
+pub use rbtree::*;
+mod llrb;
+pub use llrb::*;
+mod ordered_set;
+pub use ordered_set::*;
+mod interval_tree;
+pub use interval_tree::*;
+mod dot;
+pub use dot::*;
+mod display;
+#[cfg(feature = "serde")]
+mod serde_impl;
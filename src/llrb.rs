@@ -0,0 +1,581 @@
+use crate::{ordered_set::{above, below}, RBInvariantError};
+use std::{
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+    ptr,
+};
+
+type Link<T> = Option<Box<LLRBNode<T>>>;
+
+///
+/// a node of a [`LLRBTree`]. red is the colour of the link from its parent.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct LLRBNode<T> {
+    pub(crate) value: T,
+    pub(crate) left: Link<T>,
+    pub(crate) right: Link<T>,
+    pub(crate) red: bool,
+    pub(crate) length: usize,
+}
+
+///
+/// Sedgewick's left-leaning red-black tree.
+/// Red links lean left and no node has two red links, so a black node with its red left child
+/// is exactly a 3-node of a 2-3 tree, and insertion and deletion follow those of the 2-3 tree step by step.
+/// It has the same set operations as [`Tree234`](crate::Tree234), and is a multiset in the same way.
+///
+/// # Example
+/// ```
+/// use algorithm::LLRBTree;
+/// let mut tree = LLRBTree::new();
+/// for i in [5, 1, 4, 2, 3] {
+///     tree.insert(i);
+/// }
+/// assert!(tree.find(&3));
+/// assert!(tree.delete(&3));
+/// assert_eq!(tree.iter().collect::<Vec<_>>(), vec![&1, &2, &4, &5]);
+/// assert_eq!(tree.range(2..5).collect::<Vec<_>>(), vec![&2, &4]);
+/// assert_eq!(tree.validate(), Ok(()));
+/// ```
+///
+#[derive(Clone)]
+pub struct LLRBTree<T> {
+    root: Link<T>,
+}
+
+impl<T> Default for LLRBTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug> Debug for LLRBTree<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for LLRBTree<T> {
+    ///
+    /// make a LLRBTree from a Vec in O(n log n).
+    /// duplicated values are stored only once.
+    ///
+    fn from(mut v: Vec<T>) -> Self {
+        v.sort();
+        v.dedup();
+        let mut tree = Self::new();
+        for value in v {
+            tree.insert(value);
+        }
+        tree
+    }
+}
+
+impl<T> LLRBTree<T> {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    ///
+    /// clear the tree.
+    ///
+    pub fn clear(&mut self) {
+        self.root = None;
+    }
+
+    ///
+    /// check if the tree is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    ///
+    /// return the number of values in the tree in O(1).
+    ///
+    pub fn len(&self) -> usize {
+        length(&self.root)
+    }
+
+    ///
+    /// return the smallest value in the tree.
+    ///
+    pub fn first(&self) -> Option<&T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = &node.left {
+            node = left;
+        }
+        Some(&node.value)
+    }
+
+    ///
+    /// return the largest value in the tree.
+    ///
+    pub fn last(&self) -> Option<&T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = &node.right {
+            node = right;
+        }
+        Some(&node.value)
+    }
+
+    ///
+    /// remove the smallest value from the tree and return it.
+    ///
+    pub fn pop_first(&mut self) -> Option<T> {
+        let mut root = self.root.take()?;
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.red = true;
+        }
+        let (root, value) = delete_min(root);
+        self.set_root(root);
+        Some(value)
+    }
+
+    ///
+    /// remove the largest value from the tree and return it.
+    ///
+    pub fn pop_last(&mut self) -> Option<T> {
+        let mut root = self.root.take()?;
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.red = true;
+        }
+        let (root, value) = delete_max(root);
+        self.set_root(root);
+        Some(value)
+    }
+
+    ///
+    /// iterate over the values in ascending order.
+    ///
+    pub fn iter(&self) -> LLRBIter<'_, T> {
+        let mut iter = LLRBIter { stack: vec![] };
+        iter.push_leftmost(&self.root);
+        iter
+    }
+
+    ///
+    /// return the number of nodes on the longest path from the root to a leaf. an empty tree has height 0.
+    ///
+    pub fn height(&self) -> usize {
+        fn height<T>(link: &Link<T>) -> usize {
+            link.as_ref().map_or(0, |node| 1 + height(&node.left).max(height(&node.right)))
+        }
+        height(&self.root)
+    }
+
+    ///
+    /// return the number of black links on the path from the root to the leftmost leaf,
+    /// which is the height of the corresponding 2-3 tree.
+    ///
+    pub fn black_height(&self) -> usize {
+        let mut link = &self.root;
+        let mut height = 0;
+        while let Some(node) = link {
+            height += usize::from(!node.red);
+            link = &node.left;
+        }
+        height
+    }
+
+    fn set_root(&mut self, root: Link<T>) {
+        self.root = root.map(|mut root| {
+            root.red = false;
+            root
+        });
+    }
+}
+
+impl<T: Ord> LLRBTree<T> {
+    ///
+    /// insert value to self. equal values are kept side by side.
+    ///
+    pub fn insert(&mut self, value: T) {
+        let root = insert(self.root.take(), value);
+        self.set_root(Some(root));
+    }
+
+    ///
+    /// insert value to self only if self does not contain it yet.
+    /// return true if value was inserted.
+    ///
+    pub fn insert_unique(&mut self, value: T) -> bool {
+        if self.find(&value) {
+            return false;
+        }
+        self.insert(value);
+        true
+    }
+
+    ///
+    /// if value is in self, delete one occurrence of it and return true. else return false
+    ///
+    pub fn delete(&mut self, value: &T) -> bool {
+        if !self.find(value) {
+            return false;
+        }
+        let mut root = self.root.take().unwrap();
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.red = true;
+        }
+        let root = delete(root, value);
+        self.set_root(root);
+        true
+    }
+
+    ///
+    /// check if the tree contains value.
+    ///
+    pub fn find(&self, value: &T) -> bool {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            link = match value.cmp(&node.value) {
+                std::cmp::Ordering::Less => &node.left,
+                std::cmp::Ordering::Greater => &node.right,
+                std::cmp::Ordering::Equal => return true,
+            };
+        }
+        false
+    }
+
+    ///
+    /// return the smallest value which lies above the bound.
+    ///
+    pub fn lower_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        let mut link = &self.root;
+        let mut found = None;
+        while let Some(node) = link {
+            if above(&node.value, bound) {
+                found = Some(&node.value);
+                link = &node.left;
+            } else {
+                link = &node.right;
+            }
+        }
+        found
+    }
+
+    ///
+    /// return the largest value which lies below the bound.
+    ///
+    pub fn upper_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        let mut link = &self.root;
+        let mut found = None;
+        while let Some(node) = link {
+            if below(&node.value, bound) {
+                found = Some(&node.value);
+                link = &node.right;
+            } else {
+                link = &node.left;
+            }
+        }
+        found
+    }
+
+    ///
+    /// iterate over the values inside range in ascending order.
+    ///
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> LLRBRange<'_, T> {
+        let first = self.lower_bound(range.start_bound());
+        let mut last = self.upper_bound(range.end_bound());
+        if matches!((first, last), (Some(first), Some(last)) if first > last) {
+            last = None;
+        }
+        // 範囲の始まりより後ろの値だけを積んで, 最初の値から始める.
+        let mut iter = LLRBIter { stack: vec![] };
+        let mut link = &self.root;
+        while let Some(node) = link {
+            if above(&node.value, range.start_bound()) {
+                iter.stack.push(node);
+                link = &node.left;
+            } else {
+                link = &node.right;
+            }
+        }
+        LLRBRange { iter, last }
+    }
+
+    ///
+    /// check the invariants of the tree: the root is black, red links lean left and never follow each other,
+    /// every path has the same number of black links, the values are sorted and the cached lengths are right.
+    ///
+    pub fn validate(&self) -> Result<(), RBInvariantError> {
+        if self.root.as_ref().is_some_and(|root| root.red) {
+            return Err(RBInvariantError::RedRoot);
+        }
+        validate(&self.root, 0, None, None, &mut None, 0)?;
+        Ok(())
+    }
+}
+
+fn is_red<T>(link: &Link<T>) -> bool {
+    link.as_ref().is_some_and(|node| node.red)
+}
+
+fn length<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.length)
+}
+
+impl<T> LLRBNode<T> {
+    fn update(&mut self) {
+        self.length = length(&self.left) + 1 + length(&self.right);
+    }
+
+    fn left_left_is_red(&self) -> bool {
+        self.left.as_ref().is_some_and(|left| is_red(&left.left))
+    }
+
+    fn right_left_is_red(&self) -> bool {
+        self.right.as_ref().is_some_and(|right| is_red(&right.left))
+    }
+}
+
+///
+/// turn a right-leaning red link of h to the left.
+///
+fn rotate_left<T>(mut h: Box<LLRBNode<T>>) -> Box<LLRBNode<T>> {
+    let mut x = h.right.take().unwrap();
+    h.right = x.left.take();
+    x.red = h.red;
+    h.red = true;
+    h.update();
+    x.left = Some(h);
+    x.update();
+    x
+}
+
+///
+/// turn a left-leaning red link of h to the right.
+///
+fn rotate_right<T>(mut h: Box<LLRBNode<T>>) -> Box<LLRBNode<T>> {
+    let mut x = h.left.take().unwrap();
+    h.left = x.right.take();
+    x.red = h.red;
+    h.red = true;
+    h.update();
+    x.right = Some(h);
+    x.update();
+    x
+}
+
+///
+/// flip the colours of h and its children: split a 4-node, or the reverse.
+///
+fn flip_colors<T>(h: &mut LLRBNode<T>) {
+    h.red = !h.red;
+    for child in [&mut h.left, &mut h.right].into_iter().flatten() {
+        child.red = !child.red;
+    }
+}
+
+///
+/// restore the left-leaning invariants of h on the way up.
+///
+fn balance<T>(mut h: Box<LLRBNode<T>>) -> Box<LLRBNode<T>> {
+    if is_red(&h.right) && !is_red(&h.left) {
+        h = rotate_left(h);
+    }
+    if is_red(&h.left) && h.left_left_is_red() {
+        h = rotate_right(h);
+    }
+    if is_red(&h.left) && is_red(&h.right) {
+        flip_colors(&mut h);
+    }
+    h.update();
+    h
+}
+
+fn insert<T: Ord>(link: Link<T>, value: T) -> Box<LLRBNode<T>> {
+    let Some(mut h) = link else {
+        return Box::new(LLRBNode { value, left: None, right: None, red: true, length: 1 });
+    };
+    if value < h.value {
+        h.left = Some(insert(h.left.take(), value));
+    } else {
+        h.right = Some(insert(h.right.take(), value));
+    }
+    balance(h)
+}
+
+///
+/// make h.left or one of its children red, borrowing from the right sibling if it can.
+///
+fn move_red_left<T>(mut h: Box<LLRBNode<T>>) -> Box<LLRBNode<T>> {
+    flip_colors(&mut h);
+    if h.right_left_is_red() {
+        h.right = Some(rotate_right(h.right.take().unwrap()));
+        h = rotate_left(h);
+        flip_colors(&mut h);
+    }
+    h
+}
+
+///
+/// make h.right or one of its children red, borrowing from the left sibling if it can.
+///
+fn move_red_right<T>(mut h: Box<LLRBNode<T>>) -> Box<LLRBNode<T>> {
+    flip_colors(&mut h);
+    if h.left_left_is_red() {
+        h = rotate_right(h);
+        flip_colors(&mut h);
+    }
+    h
+}
+
+fn delete_min<T>(mut h: Box<LLRBNode<T>>) -> (Link<T>, T) {
+    if h.left.is_none() {
+        // 左に傾いているので, 左の子がなければ右の子もない.
+        return (None, h.value);
+    }
+    if !is_red(&h.left) && !h.left_left_is_red() {
+        h = move_red_left(h);
+    }
+    let (left, value) = delete_min(h.left.take().unwrap());
+    h.left = left;
+    (Some(balance(h)), value)
+}
+
+fn delete_max<T>(mut h: Box<LLRBNode<T>>) -> (Link<T>, T) {
+    if is_red(&h.left) {
+        h = rotate_right(h);
+    }
+    if h.right.is_none() {
+        return (None, h.value);
+    }
+    if !is_red(&h.right) && !h.right_left_is_red() {
+        h = move_red_right(h);
+    }
+    let (right, value) = delete_max(h.right.take().unwrap());
+    h.right = right;
+    (Some(balance(h)), value)
+}
+
+///
+/// delete one occurrence of value, which must be in the subtree rooted at h.
+///
+fn delete<T: Ord>(mut h: Box<LLRBNode<T>>, value: &T) -> Link<T> {
+    if value < &h.value {
+        if !is_red(&h.left) && !h.left_left_is_red() {
+            h = move_red_left(h);
+        }
+        h.left = delete(h.left.take().unwrap(), value);
+    } else {
+        if is_red(&h.left) {
+            h = rotate_right(h);
+        }
+        if value == &h.value && h.right.is_none() {
+            return None;
+        }
+        // 左から借りるときに回転すると, 元のhは右の部分木に移り, 等しい値が上がってくることがある.
+        let mut rotated = false;
+        if !is_red(&h.right) && !h.right_left_is_red() {
+            rotated = h.left_left_is_red();
+            h = move_red_right(h);
+        }
+        if value == &h.value && !rotated {
+            // 右の部分木の最小値で置き換える.
+            let (right, successor) = delete_min(h.right.take().unwrap());
+            h.value = successor;
+            h.right = right;
+        } else {
+            h.right = delete(h.right.take().unwrap(), value);
+        }
+    }
+    Some(balance(h))
+}
+
+fn validate<T: Ord>(
+    link: &Link<T>,
+    depth: usize,
+    lower: Option<&T>,
+    upper: Option<&T>,
+    black_height: &mut Option<usize>,
+    blacks: usize,
+) -> Result<usize, RBInvariantError> {
+    let Some(node) = link else {
+        let expected = *black_height.get_or_insert(blacks);
+        if expected != blacks {
+            return Err(RBInvariantError::UnevenBlackHeight { expected, found: blacks });
+        }
+        return Ok(0);
+    };
+    if is_red(&node.right) {
+        return Err(RBInvariantError::RightLeaningRed { depth });
+    }
+    if node.red && is_red(&node.left) {
+        return Err(RBInvariantError::RedRed { depth });
+    }
+    if lower.is_some_and(|lower| lower > &node.value) || upper.is_some_and(|upper| &node.value > upper) {
+        return Err(RBInvariantError::OutOfRange { depth });
+    }
+    let blacks = blacks + usize::from(!node.red);
+    let actual = validate(&node.left, depth + 1, lower, Some(&node.value), black_height, blacks)?
+        + 1
+        + validate(&node.right, depth + 1, Some(&node.value), upper, black_height, blacks)?;
+    if node.length != actual {
+        return Err(RBInvariantError::LengthMismatch { depth, cached: node.length, actual });
+    }
+    Ok(actual)
+}
+
+///
+/// an iterator over the values of a LLRBTree in ascending order. created by [`LLRBTree::iter`].
+///
+#[derive(Clone)]
+pub struct LLRBIter<'a, T> {
+    stack: Vec<&'a LLRBNode<T>>,
+}
+
+impl<'a, T> LLRBIter<'a, T> {
+    fn push_leftmost(&mut self, mut link: &'a Link<T>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for LLRBIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.push_leftmost(&node.right);
+        Some(&node.value)
+    }
+}
+
+///
+/// an iterator over a sub-range of the values of a LLRBTree. created by [`LLRBTree::range`].
+///
+#[derive(Clone)]
+pub struct LLRBRange<'a, T> {
+    iter: LLRBIter<'a, T>,
+    ///
+    /// the last value to yield. None if the range is exhausted.
+    ///
+    last: Option<&'a T>,
+}
+
+impl<'a, T> Iterator for LLRBRange<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let last = self.last?;
+        let value = self.iter.next()?;
+        if ptr::eq(value, last) {
+            self.last = None;
+        }
+        Some(value)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LLRBTree<T> {
+    type Item = &'a T;
+    type IntoIter = LLRBIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
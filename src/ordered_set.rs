@@ -0,0 +1,287 @@
+use crate::{AugmentedRBTree, BTree, Iter, LLRBIter, LLRBRange, LLRBTree, Monoid, RBIter, RBRange, Range};
+use std::{
+    collections::{btree_set, BTreeSet},
+    ops::{Bound, RangeBounds},
+};
+
+///
+/// the operations shared by the ordered sets of this crate and [`BTreeSet`],
+/// so that an algorithm can be written once and run on any of them.
+/// the methods follow the names and the set semantics of `BTreeSet`:
+/// `insert` does nothing if an equal value is already there, and `remove` deletes at most one value.
+/// an inverted range yields nothing, except that `BTreeSet` panics on it.
+///
+/// # Example
+/// ```
+/// use algorithm::{OrderedSet, RBTree, Tree234};
+/// use std::collections::BTreeSet;
+///
+/// fn count_distinct<S: OrderedSet<i32> + Default>(values: &[i32]) -> usize {
+///     let mut set = S::default();
+///     for &value in values {
+///         set.insert(value);
+///     }
+///     set.len()
+/// }
+///
+/// let values = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+/// assert_eq!(count_distinct::<Tree234<_>>(&values), 7);
+/// assert_eq!(count_distinct::<RBTree<_>>(&values), 7);
+/// assert_eq!(count_distinct::<BTreeSet<_>>(&values), 7);
+/// ```
+///
+pub trait OrderedSet<T: Ord> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+    type Range<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    ///
+    /// insert value unless self contains it. return true if value was inserted.
+    ///
+    fn insert(&mut self, value: T) -> bool;
+
+    ///
+    /// remove one occurrence of value. return true if value was in self.
+    ///
+    fn remove(&mut self, value: &T) -> bool;
+
+    ///
+    /// check if self contains value.
+    ///
+    fn contains(&self, value: &T) -> bool;
+
+    ///
+    /// iterate over the values inside range in ascending order.
+    ///
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Self::Range<'_>;
+
+    ///
+    /// return the smallest value.
+    ///
+    fn first(&self) -> Option<&T>;
+
+    ///
+    /// return the largest value.
+    ///
+    fn last(&self) -> Option<&T>;
+
+    ///
+    /// return the number of values.
+    ///
+    fn len(&self) -> usize;
+
+    ///
+    /// check if self has no values.
+    ///
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// iterate over the values in ascending order.
+    ///
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+impl<T: Ord, const B: usize, M: Monoid<T>> OrderedSet<T> for BTree<T, B, M> {
+    type Iter<'a>
+        = Iter<'a, T, B, M>
+    where
+        Self: 'a,
+        T: 'a;
+    type Range<'a>
+        = Range<'a, T, B, M>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn insert(&mut self, value: T) -> bool {
+        self.insert_unique(value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        self.delete(value)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.find(value)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Self::Range<'_> {
+        BTree::range(self, range)
+    }
+
+    fn first(&self) -> Option<&T> {
+        BTree::first(self)
+    }
+
+    fn last(&self) -> Option<&T> {
+        BTree::last(self)
+    }
+
+    fn len(&self) -> usize {
+        BTree::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTree::iter(self)
+    }
+}
+
+impl<T: Ord, M: Monoid<T>> OrderedSet<T> for AugmentedRBTree<T, M> {
+    type Iter<'a>
+        = RBIter<'a, T, M>
+    where
+        Self: 'a,
+        T: 'a;
+    type Range<'a>
+        = RBRange<'a, T, M>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn insert(&mut self, value: T) -> bool {
+        self.insert_unique(value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        self.delete(value)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        AugmentedRBTree::contains(self, value)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Self::Range<'_> {
+        AugmentedRBTree::range(self, range)
+    }
+
+    fn first(&self) -> Option<&T> {
+        AugmentedRBTree::first(self)
+    }
+
+    fn last(&self) -> Option<&T> {
+        AugmentedRBTree::last(self)
+    }
+
+    fn len(&self) -> usize {
+        AugmentedRBTree::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        AugmentedRBTree::iter(self)
+    }
+}
+
+impl<T: Ord> OrderedSet<T> for LLRBTree<T> {
+    type Iter<'a>
+        = LLRBIter<'a, T>
+    where
+        T: 'a;
+    type Range<'a>
+        = LLRBRange<'a, T>
+    where
+        T: 'a;
+
+    fn insert(&mut self, value: T) -> bool {
+        self.insert_unique(value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        self.delete(value)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.find(value)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Self::Range<'_> {
+        LLRBTree::range(self, range)
+    }
+
+    fn first(&self) -> Option<&T> {
+        LLRBTree::first(self)
+    }
+
+    fn last(&self) -> Option<&T> {
+        LLRBTree::last(self)
+    }
+
+    fn len(&self) -> usize {
+        LLRBTree::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        LLRBTree::iter(self)
+    }
+}
+
+impl<T: Ord> OrderedSet<T> for BTreeSet<T> {
+    type Iter<'a>
+        = btree_set::Iter<'a, T>
+    where
+        T: 'a;
+    type Range<'a>
+        = btree_set::Range<'a, T>
+    where
+        T: 'a;
+
+    fn insert(&mut self, value: T) -> bool {
+        BTreeSet::insert(self, value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        BTreeSet::remove(self, value)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        BTreeSet::contains(self, value)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Self::Range<'_> {
+        BTreeSet::range(self, range)
+    }
+
+    fn first(&self) -> Option<&T> {
+        BTreeSet::first(self)
+    }
+
+    fn last(&self) -> Option<&T> {
+        BTreeSet::last(self)
+    }
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeSet::iter(self)
+    }
+}
+
+///
+/// check if value lies above the bound as a lower bound.
+///
+pub(crate) fn above<T: Ord>(value: &T, bound: Bound<&T>) -> bool {
+    match bound {
+        Bound::Included(bound) => value >= bound,
+        Bound::Excluded(bound) => value > bound,
+        Bound::Unbounded => true,
+    }
+}
+
+///
+/// check if value lies below the bound as an upper bound.
+///
+pub(crate) fn below<T: Ord>(value: &T, bound: Bound<&T>) -> bool {
+    match bound {
+        Bound::Included(bound) => value <= bound,
+        Bound::Excluded(bound) => value < bound,
+        Bound::Unbounded => true,
+    }
+}
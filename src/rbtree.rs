@@ -1,51 +1,855 @@
-use std::collections::BTreeMap;
+use crate::{ordered_set::{above, below}, Monoid, Tree234};
+use std::{
+    fmt::{Debug, Display},
+    ops::{Bound, RangeBounds},
+    ptr,
+};
 
-pub enum _RBTree<T> {
-    Red{data: T, left: RBTree<T>, right: RBTree<T>},
-    Black{data: T, left: RBTree<T>, right: RBTree<T>},
+///
+/// a node of a [`AugmentedRBTree`] with its colour.
+/// length is the number of values in the subtree rooted at the node, and aggregate is their product under M.
+///
+pub enum _RBTree<T, M: Monoid<T> = ()> {
+    Red{data: T, left: AugmentedRBTree<T, M>, right: AugmentedRBTree<T, M>, length: usize, aggregate: M::Value},
+    Black{data: T, left: AugmentedRBTree<T, M>, right: AugmentedRBTree<T, M>, length: usize, aggregate: M::Value},
 }
 
-pub enum RBTree<T> {
+///
+/// A red-black tree: a binary search tree whose nodes are red or black,
+/// where no red node has a red child and every path from the root to Nil has the same number of black nodes.
+/// The root is always black, so the height is at most 2 log2(n + 1).
+/// Insertion rebalances with Okasaki's rotations, and deletion with Kahrs' rebalancing.
+/// Like [`Tree234`](crate::Tree234), the tree is a multiset.
+///
+/// M is a [`Monoid`] whose product over each subtree is cached in the node, see [`AugmentedRBTree::fold`].
+/// The default `()` caches nothing, which is [`RBTree`].
+///
+/// # Example
+/// ```
+/// use algorithm::{AugmentedRBTree, Sum};
+/// let mut tree = AugmentedRBTree::<i32, Sum>::new();
+/// for i in [3, 1, 4, 1, 5] {
+///     tree.insert(i);
+/// }
+/// assert_eq!(tree.fold(2..), 12);
+/// assert_eq!(tree.aggregate(), 14);
+/// ```
+///
+pub enum AugmentedRBTree<T, M: Monoid<T> = ()> {
     Nil,
-    Node(Box<_RBTree<T>>)
+    Node(Box<_RBTree<T, M>>)
 }
 
-impl<T> RBTree<T> {
+///
+/// a red-black tree without cached aggregates.
+///
+/// # Example
+/// ```
+/// use algorithm::RBTree;
+/// let mut tree = RBTree::new();
+/// for i in [3, 1, 4, 1, 5] {
+///     tree.insert(i);
+/// }
+/// assert!(tree.contains(&4));
+/// assert_eq!(tree.len(), 5);
+/// assert!(tree.delete(&1));
+/// assert_eq!(tree.iter().collect::<Vec<_>>(), vec![&1, &3, &4, &5]);
+/// assert_eq!(tree.validate(), Ok(()));
+/// ```
+///
+pub type RBTree<T> = AugmentedRBTree<T>;
+
+///
+/// a broken invariant of a RBTree found by [`AugmentedRBTree::validate`]. depth is 0 at the root.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RBInvariantError {
+    /// the root is red.
+    RedRoot,
+    /// a red node at depth has a red child.
+    RedRed { depth: usize },
+    /// a path to Nil has a different number of black nodes from the first path.
+    UnevenBlackHeight { expected: usize, found: usize },
+    /// a value at depth is outside the values of its ancestors.
+    OutOfRange { depth: usize },
+    /// the cached number of values of a subtree is wrong.
+    LengthMismatch { depth: usize, cached: usize, actual: usize },
+    /// a node at depth has a red right child. only a [`LLRBTree`](crate::LLRBTree) reports this.
+    RightLeaningRed { depth: usize },
+}
+
+impl Display for RBInvariantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RBInvariantError::RedRoot => write!(f, "the root is red"),
+            RBInvariantError::RedRed { depth } => write!(f, "a red node at depth {depth} has a red child"),
+            RBInvariantError::UnevenBlackHeight { expected, found } => {
+                write!(f, "a path has {found} black nodes while another path has {expected}")
+            }
+            RBInvariantError::OutOfRange { depth } => {
+                write!(f, "a value at depth {depth} is outside the values of its ancestors")
+            }
+            RBInvariantError::LengthMismatch { depth, cached, actual } => {
+                write!(f, "a node at depth {depth} caches {cached} values but its subtree has {actual}")
+            }
+            RBInvariantError::RightLeaningRed { depth } => write!(f, "a node at depth {depth} has a red right child"),
+        }
+    }
+}
+
+impl std::error::Error for RBInvariantError {}
+
+impl<T, M: Monoid<T>> Default for AugmentedRBTree<T, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, M: Monoid<T>> Clone for AugmentedRBTree<T, M> {
+    fn clone(&self) -> Self {
+        match self {
+            AugmentedRBTree::Nil => AugmentedRBTree::Nil,
+            AugmentedRBTree::Node(_) => Self::node(self.is_red(), self.left().clone(), self.data().clone(), self.right().clone()),
+        }
+    }
+}
+
+impl<T: Debug, M: Monoid<T>> Debug for AugmentedRBTree<T, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord, M: Monoid<T>> From<Vec<T>> for AugmentedRBTree<T, M> {
+    ///
+    /// make a RBTree from a Vec in O(n log n).
+    /// duplicated values are stored only once, as in the other trees.
+    ///
+    fn from(mut v: Vec<T>) -> Self {
+        v.sort();
+        v.dedup();
+        let mut tree = Self::new();
+        for value in v {
+            tree.insert(value);
+        }
+        tree
+    }
+}
+
+impl<T> From<Tree234<T>> for RBTree<T> {
+    ///
+    /// convert a 2-3-4 tree into a red-black tree in O(n), keeping every value.
+    /// each node of the 2-3-4 tree becomes a black node holding its middle value,
+    /// with the other values as red children, so the black height equals the height of the 2-3-4 tree.
+    /// ```rust
+    /// use algorithm::{RBTree, Tree234};
+    /// let tree = Tree234::from((0..100).collect::<Vec<_>>());
+    /// let height = tree.height();
+    /// let rb = RBTree::from(tree);
+    /// assert_eq!(rb.black_height(), height);
+    /// assert_eq!(rb.validate(), Ok(()));
+    /// ```
+    ///
+    fn from(tree: Tree234<T>) -> Self {
+        if tree.is_empty() {
+            return RBTree::Nil;
+        }
+        let mut data = tree.data.into_iter();
+        let mut children = tree.children.into_iter().map(|child| Self::from(*child));
+        let mut child = || children.next().unwrap_or(RBTree::Nil);
+        // 2-nodeは黒1つ, 3-nodeは左に赤を持つ黒, 4-nodeは両側に赤を持つ黒になる.
+        match (data.next(), data.next(), data.next()) {
+            (Some(a), None, None) => Self::black(child(), a, child()),
+            (Some(a), Some(b), None) => {
+                let left = Self::red(child(), a, child());
+                Self::black(left, b, child())
+            }
+            (Some(a), Some(b), Some(c)) => {
+                let left = Self::red(child(), a, child());
+                let right = Self::red(child(), c, child());
+                Self::black(left, b, right)
+            }
+            _ => unreachable!("a node of a non-empty Tree234 has 1 to 3 values"),
+        }
+    }
+}
+
+impl<T> From<RBTree<T>> for Tree234<T> {
+    ///
+    /// convert a red-black tree into a 2-3-4 tree in O(n), keeping every value.
+    /// each black node and its red children become one node of the 2-3-4 tree,
+    /// so the height equals the black height of the red-black tree.
+    /// ```rust
+    /// use algorithm::{RBTree, Tree234};
+    /// let rb = RBTree::from((0..100).collect::<Vec<_>>());
+    /// let black_height = rb.black_height();
+    /// let tree = Tree234::from(rb);
+    /// assert_eq!(tree.height(), black_height);
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    ///
+    fn from(tree: RBTree<T>) -> Self {
+        let Some((_, left, data, right)) = tree.split() else {
+            return Tree234::new();
+        };
+        // 黒いノードと赤い子をまとめて1つのノードにする. 根が赤くても同じように扱う.
+        let mut node = Tree234::new();
+        absorb(&mut node, left);
+        node.data.push(data);
+        absorb(&mut node, right);
+        node.update();
+        node
+    }
+}
+
+///
+/// append a child of a black node to the 2-3-4 node made from it.
+/// a red child is merged into the node, and a black child becomes a child of the node.
+///
+fn absorb<T>(node: &mut Tree234<T>, side: RBTree<T>) {
+    let push_child = |node: &mut Tree234<T>, child: RBTree<T>| {
+        if !child.empty() {
+            node.children.push(Box::new(Tree234::from(child)));
+        }
+    };
+    if side.is_red() {
+        let (_, a, x, b) = side.split().unwrap();
+        push_child(node, a);
+        node.data.push(x);
+        push_child(node, b);
+    } else {
+        push_child(node, side);
+    }
+}
+
+impl<T, M: Monoid<T>> AugmentedRBTree<T, M> {
     pub fn new() -> Self {
-        RBTree::Nil
+        AugmentedRBTree::Nil
+    }
+    fn red(left: AugmentedRBTree<T, M>, data: T, right: AugmentedRBTree<T, M>) -> Self {
+        Self::node(true, left, data, right)
     }
-    fn red(data: T) -> Self {
-        RBTree::Node(Box::new(_RBTree::Red{data: data, left: RBTree::new(), right: RBTree::new()}))
+    fn black(left: AugmentedRBTree<T, M>, data: T, right: AugmentedRBTree<T, M>) -> Self {
+        Self::node(false, left, data, right)
     }
-    fn black(data: T) -> Self {
-        RBTree::Node(Box::new(_RBTree::Black{data: data, left: RBTree::new(), right: RBTree::new()}))
+    ///
+    /// make a node, caching the number of values below it and their aggregate.
+    ///
+    fn node(red: bool, left: AugmentedRBTree<T, M>, data: T, right: AugmentedRBTree<T, M>) -> Self {
+        let length = left.len() + 1 + right.len();
+        let aggregate = M::op(&M::op(&left.aggregate(), &M::single(&data)), &right.aggregate());
+        let node = if red {
+            _RBTree::Red{data, left, right, length, aggregate}
+        } else {
+            _RBTree::Black{data, left, right, length, aggregate}
+        };
+        AugmentedRBTree::Node(Box::new(node))
+    }
+    ///
+    /// take a node apart into (is red, left, data, right). None for Nil.
+    ///
+    fn split(self) -> Option<(bool, Self, T, Self)> {
+        match self {
+            AugmentedRBTree::Node(node) => match *node {
+                _RBTree::Red{data, left, right, ..} => Some((true, left, data, right)),
+                _RBTree::Black{data, left, right, ..} => Some((false, left, data, right)),
+            },
+            AugmentedRBTree::Nil => None,
+        }
     }
     pub fn empty(&self) -> bool {
+        matches!(self, AugmentedRBTree::Nil)
+    }
+
+    ///
+    /// check if the tree is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.empty()
+    }
+
+    ///
+    /// return the number of values in the tree in O(1).
+    ///
+    pub fn len(&self) -> usize {
         match self {
-            RBTree::Nil => true,
-            _ => false,
+            AugmentedRBTree::Node(node) => match node.as_ref() {
+                _RBTree::Red{length, ..} | _RBTree::Black{length, ..} => *length,
+            },
+            AugmentedRBTree::Nil => 0,
+        }
+    }
+
+    ///
+    /// return the product of all values under M in O(1). the identity for an empty tree.
+    ///
+    pub fn aggregate(&self) -> M::Value {
+        match self {
+            AugmentedRBTree::Node(node) => match node.as_ref() {
+                _RBTree::Red{aggregate, ..} | _RBTree::Black{aggregate, ..} => aggregate.clone(),
+            },
+            AugmentedRBTree::Nil => M::identity(),
         }
     }
-    fn left(&self) -> &RBTree<T> {
+
+    ///
+    /// clear the tree.
+    pub fn clear(&mut self) {
+        *self = AugmentedRBTree::Nil;
+    }
+
+    pub(crate) fn left(&self) -> &AugmentedRBTree<T, M> {
         match self {
-            RBTree::Node(node) => {
+            AugmentedRBTree::Node(node) => {
                 match node.as_ref() {
                     _RBTree::Red{left, ..} => left,
                     _RBTree::Black{left, ..} => left,
                 }
             },
-            RBTree::Nil => panic!("Nil has no children"),
+            AugmentedRBTree::Nil => panic!("Nil has no children"),
         }
     }
-    fn right(&self) -> &RBTree<T> {
+    pub(crate) fn right(&self) -> &AugmentedRBTree<T, M> {
         match self {
-            RBTree::Node(node) => {
+            AugmentedRBTree::Node(node) => {
                 match node.as_ref() {
                     _RBTree::Red{right, ..} => right,
                     _RBTree::Black{right, ..} => right,
                 }
             },
-            RBTree::Nil => panic!("Nil has no children"),
+            AugmentedRBTree::Nil => panic!("Nil has no children"),
+        }
+    }
+    pub(crate) fn data(&self) -> &T {
+        match self {
+            AugmentedRBTree::Node(node) => match node.as_ref() {
+                _RBTree::Red{data, ..} | _RBTree::Black{data, ..} => data,
+            },
+            AugmentedRBTree::Nil => panic!("Nil has no data"),
+        }
+    }
+    pub(crate) fn is_red(&self) -> bool {
+        matches!(self, AugmentedRBTree::Node(node) if matches!(node.as_ref(), _RBTree::Red{..}))
+    }
+    ///
+    /// check if self is a black node. Nil is not.
+    ///
+    fn is_black(&self) -> bool {
+        matches!(self, AugmentedRBTree::Node(node) if matches!(node.as_ref(), _RBTree::Black{..}))
+    }
+
+    ///
+    /// return the smallest value in the tree.
+    ///
+    pub fn first(&self) -> Option<&T> {
+        let mut node = self;
+        if node.empty() {
+            return None;
+        }
+        while !node.left().empty() {
+            node = node.left();
+        }
+        Some(node.data())
+    }
+
+    ///
+    /// return the largest value in the tree.
+    ///
+    pub fn last(&self) -> Option<&T> {
+        let mut node = self;
+        if node.empty() {
+            return None;
+        }
+        while !node.right().empty() {
+            node = node.right();
+        }
+        Some(node.data())
+    }
+
+    ///
+    /// return the number of nodes on the longest path from the root to Nil. Nil has height 0.
+    ///
+    pub fn height(&self) -> usize {
+        match self {
+            AugmentedRBTree::Nil => 0,
+            AugmentedRBTree::Node(_) => 1 + self.left().height().max(self.right().height()),
+        }
+    }
+
+    ///
+    /// return the number of black nodes on the path from the root to the leftmost Nil.
+    ///
+    pub fn black_height(&self) -> usize {
+        let mut node = self;
+        let mut height = 0;
+        while !node.empty() {
+            height += usize::from(node.is_black());
+            node = node.left();
+        }
+        height
+    }
+
+    ///
+    /// iterate over the values in ascending order.
+    ///
+    pub fn iter(&self) -> RBIter<'_, T, M> {
+        let mut iter = RBIter { stack: vec![] };
+        iter.push_leftmost(self);
+        iter
+    }
+
+    ///
+    /// build a tree from values in ascending order in O(n), splitting them at the middle at every node.
+    /// the levels above the lowest one are full, so only the nodes on the lowest level are made red.
+    ///
+    pub(crate) fn from_sorted_vec(values: Vec<T>) -> Self {
+        let n = values.len();
+        let full = (n + 1).ilog2() as usize;
+        Self::build_sorted(&mut values.into_iter(), n, 0, full)
+    }
+
+    fn build_sorted(values: &mut impl Iterator<Item = T>, n: usize, depth: usize, full: usize) -> Self {
+        if n == 0 {
+            return AugmentedRBTree::Nil;
+        }
+        let left = Self::build_sorted(values, (n - 1) / 2, depth + 1, full);
+        let data = values.next().unwrap();
+        let right = Self::build_sorted(values, n / 2, depth + 1, full);
+        Self::node(depth == full, left, data, right)
+    }
+
+    ///
+    /// paint the root black.
+    ///
+    fn blacken(self) -> Self {
+        match self.split() {
+            Some((_, left, data, right)) => Self::black(left, data, right),
+            None => AugmentedRBTree::Nil,
+        }
+    }
+
+    ///
+    /// repaint a black node red, making its black height 1 lower.
+    ///
+    fn sub1(self) -> Self {
+        match self.split() {
+            Some((false, left, data, right)) => Self::red(left, data, right),
+            _ => panic!("red-black invariant violated: sub1 of a node which is not black"),
+        }
+    }
+
+    ///
+    /// make a node from left, data and right, which are black at their roots or have one red-red edge below.
+    /// the red-red edge is removed by rotating it into a red node with two black children.
+    ///
+    fn balance(left: Self, data: T, right: Self) -> Self {
+        if left.is_red() && right.is_red() {
+            let (_, a, x, b) = left.split().unwrap();
+            let (_, c, z, d) = right.split().unwrap();
+            return Self::red(Self::black(a, x, b), data, Self::black(c, z, d));
+        }
+        if left.is_red() && left.left().is_red() {
+            let (_, ll, y, c) = left.split().unwrap();
+            let (_, a, x, b) = ll.split().unwrap();
+            return Self::red(Self::black(a, x, b), y, Self::black(c, data, right));
+        }
+        if left.is_red() && left.right().is_red() {
+            let (_, a, x, lr) = left.split().unwrap();
+            let (_, b, y, c) = lr.split().unwrap();
+            return Self::red(Self::black(a, x, b), y, Self::black(c, data, right));
+        }
+        if right.is_red() && right.right().is_red() {
+            let (_, b, y, rr) = right.split().unwrap();
+            let (_, c, z, d) = rr.split().unwrap();
+            return Self::red(Self::black(left, data, b), y, Self::black(c, z, d));
+        }
+        if right.is_red() && right.left().is_red() {
+            let (_, rl, z, d) = right.split().unwrap();
+            let (_, b, y, c) = rl.split().unwrap();
+            return Self::red(Self::black(left, data, b), y, Self::black(c, z, d));
+        }
+        Self::black(left, data, right)
+    }
+
+    ///
+    /// make a node from left, data and right, where the black height of left is 1 lower than that of right.
+    ///
+    fn balance_left(left: Self, data: T, right: Self) -> Self {
+        if left.is_red() {
+            let (_, a, x, b) = left.split().unwrap();
+            return Self::red(Self::black(a, x, b), data, right);
+        }
+        if right.is_black() {
+            let (_, a, y, b) = right.split().unwrap();
+            return Self::balance(left, data, Self::red(a, y, b));
+        }
+        if right.is_red() && right.left().is_black() {
+            let (_, rl, z, c) = right.split().unwrap();
+            let (_, a, y, b) = rl.split().unwrap();
+            return Self::red(Self::black(left, data, a), y, Self::balance(b, z, c.sub1()));
+        }
+        panic!("red-black invariant violated in balance_left");
+    }
+
+    ///
+    /// make a node from left, data and right, where the black height of right is 1 lower than that of left.
+    ///
+    fn balance_right(left: Self, data: T, right: Self) -> Self {
+        if right.is_red() {
+            let (_, b, y, c) = right.split().unwrap();
+            return Self::red(left, data, Self::black(b, y, c));
+        }
+        if left.is_black() {
+            let (_, a, x, b) = left.split().unwrap();
+            return Self::balance(Self::red(a, x, b), data, right);
+        }
+        if left.is_red() && left.right().is_black() {
+            let (_, a, x, lr) = left.split().unwrap();
+            let (_, b, y, c) = lr.split().unwrap();
+            return Self::red(Self::balance(a.sub1(), x, b), y, Self::black(c, data, right));
+        }
+        panic!("red-black invariant violated in balance_right");
+    }
+
+    ///
+    /// concatenate left and right, which have the same black height, after a value between them was removed.
+    ///
+    fn append(left: Self, right: Self) -> Self {
+        if left.empty() {
+            return right;
+        }
+        if right.empty() {
+            return left;
+        }
+        match (left.is_red(), right.is_red()) {
+            (true, true) => {
+                let (_, a, x, b) = left.split().unwrap();
+                let (_, c, y, d) = right.split().unwrap();
+                let bc = Self::append(b, c);
+                if bc.is_red() {
+                    let (_, b, z, c) = bc.split().unwrap();
+                    Self::red(Self::red(a, x, b), z, Self::red(c, y, d))
+                } else {
+                    Self::red(a, x, Self::red(bc, y, d))
+                }
+            }
+            (false, false) => {
+                let (_, a, x, b) = left.split().unwrap();
+                let (_, c, y, d) = right.split().unwrap();
+                let bc = Self::append(b, c);
+                if bc.is_red() {
+                    let (_, b, z, c) = bc.split().unwrap();
+                    Self::red(Self::black(a, x, b), z, Self::black(c, y, d))
+                } else {
+                    Self::balance_left(a, x, Self::black(bc, y, d))
+                }
+            }
+            (false, true) => {
+                let (_, b, x, c) = right.split().unwrap();
+                Self::red(Self::append(left, b), x, c)
+            }
+            (true, false) => {
+                let (_, a, x, b) = left.split().unwrap();
+                Self::red(a, x, Self::append(b, right))
+            }
+        }
+    }
+}
+
+impl<T: Ord, M: Monoid<T>> AugmentedRBTree<T, M> {
+    ///
+    /// make a tree from values in ascending order in O(n), like [`Tree234::from_sorted_iter`].
+    /// duplicated values are kept.
+    /// ```rust
+    /// use algorithm::RBTree;
+    /// let tree = RBTree::from_sorted_iter(vec![1, 2, 2, 3]);
+    /// assert_eq!(tree.len(), 4);
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    /// # Panics
+    /// panics if values are not sorted.
+    ///
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let values: Vec<T> = values.into_iter().collect();
+        assert!(values.is_sorted(), "values should be sorted in ascending order");
+        Self::from_sorted_vec(values)
+    }
+    ///
+    /// insert value to self. equal values are kept side by side.
+    ///
+    pub fn insert(&mut self, value: T) {
+        *self = std::mem::take(self).insert_into(value).blacken();
+    }
+
+    ///
+    /// insert value to self only if self does not contain it yet.
+    /// return true if value was inserted.
+    ///
+    pub fn insert_unique(&mut self, value: T) -> bool {
+        if self.contains(&value) {
+            return false;
+        }
+        self.insert(value);
+        true
+    }
+
+    ///
+    /// if value is in self, delete one occurrence of it and return true. else return false
+    ///
+    pub fn delete(&mut self, value: &T) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+        *self = std::mem::take(self).delete_from(value).blacken();
+        true
+    }
+
+    ///
+    /// check if the tree contains value.
+    ///
+    pub fn contains(&self, value: &T) -> bool {
+        let mut node = self;
+        while !node.empty() {
+            node = match value.cmp(node.data()) {
+                std::cmp::Ordering::Less => node.left(),
+                std::cmp::Ordering::Greater => node.right(),
+                std::cmp::Ordering::Equal => return true,
+            };
+        }
+        false
+    }
+
+    ///
+    /// return the smallest value which lies above the bound.
+    ///
+    pub fn lower_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        let mut node = self;
+        let mut found = None;
+        while !node.empty() {
+            if above(node.data(), bound) {
+                found = Some(node.data());
+                node = node.left();
+            } else {
+                node = node.right();
+            }
+        }
+        found
+    }
+
+    ///
+    /// return the largest value which lies below the bound.
+    ///
+    pub fn upper_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        let mut node = self;
+        let mut found = None;
+        while !node.empty() {
+            if below(node.data(), bound) {
+                found = Some(node.data());
+                node = node.right();
+            } else {
+                node = node.left();
+            }
+        }
+        found
+    }
+
+    ///
+    /// iterate over the values inside range in ascending order.
+    /// ```rust
+    /// use algorithm::RBTree;
+    /// let tree = RBTree::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.range(2..4).collect::<Vec<_>>(), vec![&2, &3]);
+    /// assert_eq!(tree.range(..=2).collect::<Vec<_>>(), vec![&1, &2]);
+    /// ```
+    ///
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> RBRange<'_, T, M> {
+        let first = self.lower_bound(range.start_bound());
+        let mut last = self.upper_bound(range.end_bound());
+        if matches!((first, last), (Some(first), Some(last)) if first > last) {
+            last = None;
+        }
+        // 範囲の始まりより後ろの値だけを積んで, 最初の値から始める.
+        let mut iter = RBIter { stack: vec![] };
+        let mut node = self;
+        while !node.empty() {
+            if above(node.data(), range.start_bound()) {
+                iter.stack.push(node);
+                node = node.left();
+            } else {
+                node = node.right();
+            }
         }
+        RBRange { iter, last }
     }
-}
\ No newline at end of file
+
+    ///
+    /// return the product under M of the values inside range in ascending order, in O(log n).
+    /// ```rust
+    /// use algorithm::{AugmentedRBTree, Sum};
+    /// let tree = AugmentedRBTree::<i32, Sum>::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.fold(2..4), 5);
+    /// assert_eq!(tree.fold(..), 15);
+    /// ```
+    ///
+    pub fn fold<R: RangeBounds<T>>(&self, range: R) -> M::Value {
+        self.fold_node(range.start_bound(), range.end_bound())
+    }
+
+    fn fold_node(&self, start: Bound<&T>, end: Bound<&T>) -> M::Value {
+        if self.empty() {
+            return M::identity();
+        }
+        if matches!((start, end), (Bound::Unbounded, Bound::Unbounded)) {
+            return self.aggregate();
+        }
+        let data = self.data();
+        if !above(data, start) {
+            return self.right().fold_node(start, end);
+        }
+        if !below(data, end) {
+            return self.left().fold_node(start, end);
+        }
+        // dataが範囲内なら, 左は終わり側, 右は始まり側の制約がなくなる.
+        let left = self.left().fold_node(start, Bound::Unbounded);
+        let right = self.right().fold_node(Bound::Unbounded, end);
+        M::op(&M::op(&left, &M::single(data)), &right)
+    }
+
+    ///
+    /// check the red-black invariants: the root is black, no red node has a red child,
+    /// every path to Nil has the same number of black nodes, the values are sorted,
+    /// and the cached subtree lengths are right.
+    ///
+    pub fn validate(&self) -> Result<(), RBInvariantError> {
+        if self.is_red() {
+            return Err(RBInvariantError::RedRoot);
+        }
+        self.validate_node(0, None, None, &mut None, 0)?;
+        Ok(())
+    }
+
+    ///
+    /// check the subtree rooted at self, whose values must lie between lower and upper.
+    /// blacks is the number of black nodes above self. return the number of values in the subtree.
+    ///
+    fn validate_node(
+        &self,
+        depth: usize,
+        lower: Option<&T>,
+        upper: Option<&T>,
+        black_height: &mut Option<usize>,
+        blacks: usize,
+    ) -> Result<usize, RBInvariantError> {
+        if self.empty() {
+            let expected = *black_height.get_or_insert(blacks);
+            if expected != blacks {
+                return Err(RBInvariantError::UnevenBlackHeight { expected, found: blacks });
+            }
+            return Ok(0);
+        }
+        if self.is_red() && (self.left().is_red() || self.right().is_red()) {
+            return Err(RBInvariantError::RedRed { depth });
+        }
+        let data = self.data();
+        if lower.is_some_and(|lower| lower > data) || upper.is_some_and(|upper| data > upper) {
+            return Err(RBInvariantError::OutOfRange { depth });
+        }
+        let blacks = blacks + usize::from(self.is_black());
+        let actual = self.left().validate_node(depth + 1, lower, Some(data), black_height, blacks)?
+            + 1
+            + self.right().validate_node(depth + 1, Some(data), upper, black_height, blacks)?;
+        if self.len() != actual {
+            return Err(RBInvariantError::LengthMismatch { depth, cached: self.len(), actual });
+        }
+        Ok(actual)
+    }
+
+    fn insert_into(self, value: T) -> Self {
+        let Some((red, left, data, right)) = self.split() else {
+            return Self::red(AugmentedRBTree::Nil, value, AugmentedRBTree::Nil);
+        };
+        match (red, value < data) {
+            // 赤いノードの下では回転せず, 黒いノードまで戻ってから直す.
+            (true, true) => Self::red(left.insert_into(value), data, right),
+            (true, false) => Self::red(left, data, right.insert_into(value)),
+            (false, true) => Self::balance(left.insert_into(value), data, right),
+            (false, false) => Self::balance(left, data, right.insert_into(value)),
+        }
+    }
+
+    ///
+    /// delete one occurrence of value, which must be in self.
+    /// the black height of the result is 1 lower if self is black, with the root possibly red.
+    ///
+    fn delete_from(self, value: &T) -> Self {
+        let Some((_, left, data, right)) = self.split() else {
+            return AugmentedRBTree::Nil;
+        };
+        match value.cmp(&data) {
+            std::cmp::Ordering::Less if left.is_black() => Self::balance_left(left.delete_from(value), data, right),
+            std::cmp::Ordering::Less => Self::red(left.delete_from(value), data, right),
+            std::cmp::Ordering::Greater if right.is_black() => Self::balance_right(left, data, right.delete_from(value)),
+            std::cmp::Ordering::Greater => Self::red(left, data, right.delete_from(value)),
+            std::cmp::Ordering::Equal => Self::append(left, right),
+        }
+    }
+}
+
+///
+/// an iterator over the values of a RBTree in ascending order. created by [`AugmentedRBTree::iter`].
+///
+pub struct RBIter<'a, T, M: Monoid<T> = ()> {
+    stack: Vec<&'a AugmentedRBTree<T, M>>,
+}
+
+impl<'a, T, M: Monoid<T>> RBIter<'a, T, M> {
+    fn push_leftmost(&mut self, mut node: &'a AugmentedRBTree<T, M>) {
+        while !node.empty() {
+            self.stack.push(node);
+            node = node.left();
+        }
+    }
+}
+
+impl<'a, T, M: Monoid<T>> Iterator for RBIter<'a, T, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.push_leftmost(node.right());
+        Some(node.data())
+    }
+}
+
+///
+/// an iterator over a sub-range of the values of a RBTree. created by [`AugmentedRBTree::range`].
+///
+pub struct RBRange<'a, T, M: Monoid<T> = ()> {
+    iter: RBIter<'a, T, M>,
+    ///
+    /// the last value to yield. None if the range is exhausted.
+    ///
+    last: Option<&'a T>,
+}
+
+impl<'a, T, M: Monoid<T>> Iterator for RBRange<'a, T, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let last = self.last?;
+        let value = self.iter.next()?;
+        if ptr::eq(value, last) {
+            self.last = None;
+        }
+        Some(value)
+    }
+}
+
+impl<'a, T, M: Monoid<T>> IntoIterator for &'a AugmentedRBTree<T, M> {
+    type Item = &'a T;
+    type IntoIter = RBIter<'a, T, M>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
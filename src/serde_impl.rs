@@ -0,0 +1,133 @@
+use crate::{AugmentedRBTree, BTree, Monoid, UnionFind};
+use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+///
+/// write the values of an ordered tree as a sequence in ascending order.
+///
+fn serialize_values<'a, T: Serialize + 'a, S: Serializer>(
+    len: usize,
+    values: impl Iterator<Item = &'a T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(len))?;
+    for value in values {
+        seq.serialize_element(value)?;
+    }
+    seq.end()
+}
+
+///
+/// read the values of an ordered tree, which should be in ascending order as `serialize_values` writes them.
+/// both trees follow this rule, so a sequence out of order is an error for either of them.
+///
+fn deserialize_values<'de, T: Deserialize<'de> + Ord, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<T>, D::Error> {
+    let values = Vec::<T>::deserialize(deserializer)?;
+    if !values.is_sorted() {
+        return Err(D::Error::custom("values should be sorted in ascending order"));
+    }
+    Ok(values)
+}
+
+///
+/// a tree is written as the sequence of its values in ascending order, so the shape of the nodes is not kept.
+/// ```rust
+/// use algorithm::Tree234;
+/// let tree = Tree234::from(vec![3, 1, 2]);
+/// assert_eq!(serde_json::to_string(&tree).unwrap(), "[1,2,3]");
+/// ```
+///
+impl<T: Serialize, const B: usize, M: Monoid<T>> Serialize for BTree<T, B, M> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_values(self.len(), self.iter(), serializer)
+    }
+}
+
+///
+/// read a sequence in ascending order and build the tree at once in O(n). duplicated values are kept.
+/// a sequence out of order is an error.
+///
+impl<'de, T: Deserialize<'de> + Ord, const B: usize, M: Monoid<T>> Deserialize<'de> for BTree<T, B, M> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tree = Self::from_sorted_vec(deserialize_values(deserializer)?);
+        tree.debug_validate(&T::cmp);
+        Ok(tree)
+    }
+}
+
+impl<T: Serialize, M: Monoid<T>> Serialize for AugmentedRBTree<T, M> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_values(self.len(), self.iter(), serializer)
+    }
+}
+
+///
+/// read a sequence in ascending order like [`BTree`], and build the tree at once in O(n).
+/// duplicated values are kept, and a sequence out of order is an error.
+///
+impl<'de, T: Deserialize<'de> + Ord, M: Monoid<T>> Deserialize<'de> for AugmentedRBTree<T, M> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_sorted_vec(deserialize_values(deserializer)?))
+    }
+}
+
+///
+/// a UnionFind is written as the pair of its parent array and its size array, as they are without compressing paths.
+/// only the sizes at the roots are meaningful, but the whole array is kept so that a round trip gives the same arrays.
+/// ```rust
+/// use algorithm::UnionFind;
+/// let mut uf = UnionFind::new(3);
+/// uf.unite(0, 1);
+/// assert_eq!(serde_json::to_string(&uf).unwrap(), "[[1,1,2],[1,2,1]]");
+/// ```
+///
+impl Serialize for UnionFind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.parent, &self.size).serialize(serializer)
+    }
+}
+
+///
+/// read a pair of a parent array and a size array, checking that both have the same length,
+/// that every parent is in range, that following parents always reaches a root,
+/// and that the size at each root is the number of elements which reach it.
+///
+impl<'de> Deserialize<'de> for UnionFind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (parent, size) = <(Vec<usize>, Vec<usize>)>::deserialize(deserializer)?;
+        let n = parent.len();
+        if size.len() != n {
+            return Err(D::Error::custom(format_args!("{} sizes are given for {n} elements", size.len())));
+        }
+        if let Some(x) = (0..n).find(|&x| parent[x] >= n) {
+            return Err(D::Error::custom(format_args!("parent[{x}] = {} is out of range for {n} elements", parent[x])));
+        }
+        // 根が分かった要素には根を書き込み, たどっている途中の要素には印を付ける.
+        let mut root = vec![None; n];
+        let mut on_path = vec![false; n];
+        for x in 0..n {
+            let mut path = vec![];
+            let mut y = x;
+            while root[y].is_none() && parent[y] != y {
+                if on_path[y] {
+                    return Err(D::Error::custom(format_args!("element {y} is on a cycle of parents")));
+                }
+                on_path[y] = true;
+                path.push(y);
+                y = parent[y];
+            }
+            let r = root[y].unwrap_or(y);
+            root[y] = Some(r);
+            for z in path {
+                root[z] = Some(r);
+            }
+        }
+        let mut count = vec![0; n];
+        for r in root.into_iter().flatten() {
+            count[r] += 1;
+        }
+        if let Some(r) = (0..n).find(|&r| parent[r] == r && size[r] != count[r]) {
+            return Err(D::Error::custom(format_args!("size[{r}] = {} but {} elements have the root {r}", size[r], count[r])));
+        }
+        Ok(UnionFind { parent, size })
+    }
+}
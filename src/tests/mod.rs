@@ -1 +1,123 @@
-mod test_tree234;
\ No newline at end of file
+mod test_btree;
+mod test_display;
+mod test_dot;
+mod test_interval_tree;
+mod test_llrb;
+mod test_ordered_set;
+mod test_rbtree;
+mod test_serde;
+mod test_tree234;
+mod test_tree234_arena;
+mod test_tree234_by;
+mod test_tree234_cursor;
+mod test_tree234_monoid;
+mod test_tree234_persistent;
+mod test_tree234_random;
+mod test_tree234_shape;
+mod test_tree234_trace;
+
+/// the random sequences and the model shared by the differential tests.
+#[cfg(test)]
+mod random {
+    use std::ops::Deref;
+
+    /// xorshift64, so that every run of the tests sees the same sequences.
+    pub struct XorShift(u64);
+
+    impl XorShift {
+        pub fn new(seed: u64) -> Self {
+            Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+        }
+
+        pub fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        pub fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        /// two values below n, the smaller first.
+        pub fn pair(&mut self, n: u64) -> (u64, u64) {
+            let (l, r) = (self.below(n), self.below(n));
+            (l.min(r), l.max(r))
+        }
+    }
+
+    ///
+    /// run steps random steps from seed. each step gets its number, a key below keys
+    /// and the generator for any further choice, and should apply one operation
+    /// to the tree under test and to its model and compare them.
+    ///
+    pub fn differential(seed: u64, steps: usize, keys: u64, mut step: impl FnMut(usize, u64, &mut XorShift)) {
+        let mut rng = XorShift::new(seed);
+        for i in 0..steps {
+            let key = rng.below(keys);
+            step(i, key, &mut rng);
+        }
+    }
+
+    /// a sorted Vec which keeps equal values, in the order a multiset tree keeps them.
+    #[derive(Debug, Default, Clone)]
+    pub struct Model(Vec<u64>);
+
+    impl Model {
+        pub fn insert(&mut self, key: u64) {
+            self.0.insert(self.upper(key), key);
+        }
+
+        /// insert key unless it is already there.
+        pub fn insert_unique(&mut self, key: u64) -> bool {
+            let inserted = self.0.binary_search(&key).is_err();
+            if inserted {
+                self.insert(key);
+            }
+            inserted
+        }
+
+        /// remove one key, if any.
+        pub fn delete(&mut self, key: u64) -> bool {
+            let pos = self.lower(key);
+            let found = self.0.get(pos) == Some(&key);
+            if found {
+                self.0.remove(pos);
+            }
+            found
+        }
+
+        pub fn pop_first(&mut self) -> Option<u64> {
+            (!self.0.is_empty()).then(|| self.0.remove(0))
+        }
+
+        pub fn pop_last(&mut self) -> Option<u64> {
+            self.0.pop()
+        }
+
+        /// the number of values less than key.
+        pub fn lower(&self, key: u64) -> usize {
+            self.0.partition_point(|&x| x < key)
+        }
+
+        /// the number of values not greater than key.
+        pub fn upper(&self, key: u64) -> usize {
+            self.0.partition_point(|&x| x <= key)
+        }
+    }
+
+    impl Deref for Model {
+        type Target = [u64];
+        fn deref(&self) -> &[u64] {
+            &self.0
+        }
+    }
+
+    impl From<Vec<u64>> for Model {
+        fn from(mut values: Vec<u64>) -> Self {
+            values.sort();
+            Self(values)
+        }
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::random::{self, Model};
+
+    /// a BTree of order B against the model, including split_off and append_tree.
+    fn differential<const B: usize>(steps: usize, keys: u64) {
+        let mut tree = BTree::<u64, B>::new();
+        let mut model = Model::default();
+        random::differential(B as u64, steps, keys, |step, key, rng| {
+            match rng.below(8) {
+                0..=2 => {
+                    tree.insert(key);
+                    model.insert(key);
+                }
+                3 | 4 => assert_eq!(tree.delete(&key), model.delete(key)),
+                5 => assert_eq!(tree.pop_first(), model.pop_first()),
+                6 => assert_eq!(tree.pop_last(), model.pop_last()),
+                _ => {
+                    let right = tree.split_off(&key);
+                    assert_eq!(right.len(), model.len() - model.lower(key));
+                    assert_eq!(tree.validate(), Ok(()));
+                    assert_eq!(right.validate(), Ok(()));
+                    tree.append_tree(right);
+                }
+            }
+            assert_eq!(tree.validate(), Ok(()), "B = {B}, step {step}");
+            assert_eq!(tree.len(), model.len());
+            assert!(tree.iter().eq(model.iter()), "B = {B}, step {step}");
+            assert_eq!(tree.rank(&key), model.lower(key));
+            assert_eq!(tree.count(&key), model.upper(key) - model.lower(key));
+        });
+    }
+
+    #[test]
+    fn test_differential() {
+        differential::<4>(3000, 64);
+        differential::<6>(3000, 64);
+        differential::<8>(3000, 1000);
+        differential::<16>(3000, 1000);
+        differential::<64>(3000, 10000);
+    }
+
+    fn bulk<const B: usize>() {
+        for n in [0, 1, B - 1, B, B * B, B * B * B + 7] {
+            let tree = BTree::<usize, B>::from_sorted_iter(0..n);
+            assert_eq!(tree.validate(), Ok(()));
+            assert!(tree.iter().copied().eq(0..n));
+            let mut height = 0;
+            while B.pow(height as u32) - 1 < n {
+                height += 1;
+            }
+            assert_eq!(tree.height(), height);
+            let (mut left, right) = (tree.clone(), tree);
+            let rest = left.split_off(&(n / 3));
+            assert_eq!(left.validate(), Ok(()));
+            assert_eq!(rest.validate(), Ok(()));
+            let joined = BTree::join(left, rest);
+            assert_eq!(joined.validate(), Ok(()));
+            assert!(joined.iter().eq(right.iter()));
+        }
+    }
+
+    #[test]
+    fn test_bulk() {
+        bulk::<4>();
+        bulk::<6>();
+        bulk::<32>();
+    }
+
+    #[test]
+    fn test_wide_nodes_are_lower() {
+        let values: Vec<usize> = (0..10000).collect();
+        let narrow = Tree234::from(values.clone());
+        let wide = BTree::<usize, 32>::from(values);
+        assert!(wide.height() < narrow.height());
+        let mut tree = BTree::<usize, 32>::new();
+        for i in (0..10000).rev() {
+            tree.insert(i);
+        }
+        assert!(tree.height() <= 4);
+        assert!(tree.union(&wide).eq(wide.iter()));
+    }
+}
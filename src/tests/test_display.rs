@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::rbtree::*;
+    use super::super::super::tree234::*;
+
+    #[test]
+    fn test_tree234() {
+        let mut tree = Tree234::new();
+        assert_eq!(format!("{tree}"), "[]");
+        assert_eq!(format!("{tree:#}"), "[]");
+        for i in 1..=10 {
+            tree.insert(i);
+        }
+        assert_eq!(format!("{tree}"), "[4]\n[2] [6|8]\n[1] [3] | [5] [7] [9|10]");
+        assert_eq!(
+            format!("{tree:#}"),
+            "\
+[4]
+├── [2]
+│   ├── [1]
+│   └── [3]
+└── [6|8]
+    ├── [5]
+    ├── [7]
+    └── [9|10]"
+        );
+        let tree = BTree::<&str, 6>::from(vec!["a", "b"]);
+        assert_eq!(format!("{tree:#}"), "[a|b]");
+    }
+
+    #[test]
+    fn test_rbtree() {
+        let mut tree = RBTree::new();
+        assert_eq!(format!("{tree}"), "·");
+        for i in 1..=6 {
+            tree.insert(i);
+        }
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(format!("{tree}"), "B:2\nB:1 R:4\nB:3 B:5\n· R:6");
+        assert_eq!(
+            format!("{tree:#}"),
+            "\
+B:2
+├── B:1
+└── R:4
+    ├── B:3
+    └── B:5
+        ├── ·
+        └── R:6"
+        );
+        // the children stay with their parents, so the nil siblings tell the shape apart.
+        let mut tree = RBTree::from(vec![2, 4, 6, 8, 10, 12, 14]);
+        for i in [1, 7, 15] {
+            tree.insert(i);
+        }
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(format!("{tree}"), "B:8\nB:4 B:12\nB:2 B:6 | B:10 B:14\nR:1 · | · R:7 | · R:15");
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::dijkstra::*;
+    use super::super::super::dot::*;
+    use super::super::super::rbtree::*;
+    use super::super::super::tree234::*;
+    use super::super::super::union_find::*;
+
+    #[test]
+    fn test_tree234() {
+        let tree = Tree234::from(vec![1, 2, 3, 4]);
+        assert_eq!(
+            tree.to_dot(),
+            "digraph BTree {
+    node [shape=record];
+    n0 [label=\"<c0>|3|<c1>\"];
+    n1 [label=\"1|2\"];
+    n0:c0 -> n1;
+    n2 [label=\"4\"];
+    n0:c1 -> n2;
+}
+"
+        );
+        assert_eq!(Tree234::<i32>::new().to_dot(), "digraph BTree {\n    node [shape=record];\n}\n");
+        // レコードの区切りになる文字はエスケープする.
+        let tree = Tree234::from(vec!["a|b", "{c}"]);
+        assert!(tree.to_dot().contains("[label=\"a\\|b|\\{c\\}\"]"));
+    }
+
+    #[test]
+    fn test_rbtree() {
+        let tree = RBTree::from(vec![2, 3, 1, 4]);
+        let dot = tree.to_dot();
+        // 4は3の右の赤い子で, 3の左には見えないノードを置く.
+        assert!(dot.contains("[label=\"4\", fillcolor=red];"), "{dot}");
+        assert!(dot.contains("[style=invis];"), "{dot}");
+        assert_eq!(dot.matches("fillcolor").count(), 4);
+        assert_eq!(dot.matches(" -> ").count(), 4);
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut uf = UnionFind::new(4);
+        uf.unite(0, 1);
+        uf.unite(2, 3);
+        uf.unite(1, 3);
+        assert_eq!(
+            uf.to_dot(),
+            "digraph UnionFind {
+    node [shape=circle];
+    0 -> 1;
+    1 -> 3;
+    2 -> 3;
+    3 [shape=doublecircle];
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_graph() {
+        // 0 -> 1 -> 2 と 0 -> 2 が同じ長さで, 0 -> 3 は辿れない向き.
+        let graph = vec![vec![(1, 1), (2, 2)], vec![(2, 1), (1, 0)], vec![], vec![(0, 5)]];
+        let dist = dijkstra(&graph, 0);
+        let parent = shortest_path_tree(&graph, 0, &dist);
+        assert_eq!(parent, vec![None, Some((0, 0)), Some((0, 1)), None]);
+        let dot = graph_to_dot(&graph, Some(0));
+        assert!(dot.contains("    3 [label=\"3\\n∞\"];"));
+        assert_eq!(dot.matches("color=red").count(), 2);
+        assert!(dot.contains("    1 -> 1 [label=\"0\"];"));
+        let plain = graph_to_dot(&graph, None);
+        assert!(plain.contains("    0;\n"));
+        assert!(!plain.contains("color=red"));
+    }
+}
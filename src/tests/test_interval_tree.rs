@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::interval_tree::*;
+    use super::super::super::rbtree::*;
+    use super::super::super::tree234_monoid::*;
+    use super::super::random;
+    use std::ops::Range;
+
+    /// the stabbing and overlap queries of an IntervalTree against a sorted Vec of intervals.
+    fn differential(steps: usize, keys: u64, seed: u64) {
+        let mut tree = IntervalTree::new();
+        let mut model: Vec<Range<u64>> = vec![];
+        let key = |model: &Vec<Range<u64>>, i: usize| (model[i].start, model[i].end);
+        random::differential(seed, steps, keys, |step, start, rng| {
+            let interval = start..start + rng.below(keys / 4 + 1);
+            if rng.below(3) < 2 {
+                tree.insert(interval.clone());
+                let pos = (0..model.len()).take_while(|&i| key(&model, i) <= (interval.start, interval.end)).count();
+                model.insert(pos, interval);
+            } else {
+                let found = model.iter().position(|x| *x == interval);
+                if let Some(pos) = found {
+                    model.remove(pos);
+                }
+                assert_eq!(tree.remove(&interval), found.is_some(), "step {step}");
+            }
+            assert_eq!(tree.len(), model.len());
+            assert!(tree.iter().eq(model.iter().cloned()), "step {step}");
+            assert_eq!(tree.max_end(), model.iter().map(|x| x.end).max());
+
+            let point = rng.below(keys);
+            let expected: Vec<_> = model.iter().filter(|x| x.contains(&point)).cloned().collect();
+            assert_eq!(tree.stabbing(point).collect::<Vec<_>>(), expected, "step {step}");
+            let (l, r) = rng.pair(keys);
+            let expected: Vec<_> = model.iter().filter(|x| l < r && x.start < r && l < x.end && x.start < x.end).cloned().collect();
+            assert_eq!(tree.overlapping(l..r).collect::<Vec<_>>(), expected, "step {step}");
+        });
+    }
+
+    #[test]
+    fn test_differential() {
+        differential(3000, 16, 1);
+        differential(3000, 1000, 2);
+    }
+
+    #[test]
+    fn test_empty_intervals() {
+        let tree = IntervalTree::from(vec![3..3, 1..4]);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.stabbing(3).collect::<Vec<_>>(), vec![1..4]);
+        assert_eq!(tree.overlapping(2..5).collect::<Vec<_>>(), vec![1..4]);
+        assert!(tree.contains(&(3..3)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inverted_interval() {
+        let (start, end) = (5, 2);
+        IntervalTree::new().insert(start..end);
+    }
+
+    #[test]
+    fn test_fold() {
+        let mut tree = AugmentedRBTree::<u64, Sum>::new();
+        let mut model = vec![];
+        for i in 0..200u64 {
+            let value = i * 37 % 101;
+            tree.insert(value);
+            model.push(value);
+            if i % 3 == 2 {
+                assert!(tree.delete(&(i % 101)) == model.contains(&(i % 101)));
+                if let Some(pos) = model.iter().position(|&x| x == i % 101) {
+                    model.remove(pos);
+                }
+            }
+            assert_eq!(tree.validate(), Ok(()));
+            assert_eq!(tree.aggregate(), model.iter().sum::<u64>());
+            let (l, r) = (i * 13 % 101, i * 29 % 101);
+            assert_eq!(tree.fold(l..=r), model.iter().filter(|&&x| l <= x && x <= r).sum::<u64>());
+        }
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::llrb::*;
+    use super::super::super::rbtree::*;
+    use super::super::super::tree234::*;
+    use super::super::random;
+    use std::ops::Bound;
+
+    /// a LLRBTree and a RBTree against a Tree234, which serves as the model.
+    fn differential(steps: usize, keys: u64, seed: u64) {
+        let mut tree234 = Tree234::new();
+        let mut rbtree = RBTree::new();
+        let mut llrb = LLRBTree::new();
+        random::differential(seed, steps, keys, |step, key, rng| {
+            match rng.below(10) {
+                0..=2 => {
+                    tree234.insert(key);
+                    rbtree.insert(key);
+                    llrb.insert(key);
+                }
+                3 => {
+                    let inserted = tree234.insert_unique(key);
+                    assert_eq!(rbtree.insert_unique(key), inserted);
+                    assert_eq!(llrb.insert_unique(key), inserted, "step {step}");
+                }
+                4 => {
+                    let first = tree234.pop_first();
+                    if let Some(first) = first {
+                        assert!(rbtree.delete(&first));
+                    }
+                    assert_eq!(llrb.pop_first(), first, "step {step}");
+                }
+                5 => {
+                    let last = tree234.pop_last();
+                    if let Some(last) = last {
+                        assert!(rbtree.delete(&last));
+                    }
+                    assert_eq!(llrb.pop_last(), last, "step {step}");
+                }
+                _ => {
+                    let found = tree234.delete(&key);
+                    assert_eq!(rbtree.delete(&key), found);
+                    assert_eq!(llrb.delete(&key), found, "step {step}");
+                }
+            }
+            assert_eq!(rbtree.validate(), Ok(()));
+            assert_eq!(llrb.validate(), Ok(()), "step {step}");
+            assert_eq!(llrb.len(), tree234.len());
+            assert!(llrb.iter().eq(tree234.iter()), "step {step}");
+            assert!(rbtree.iter().eq(tree234.iter()), "step {step}");
+            assert_eq!(llrb.find(&key), tree234.find(&key));
+            assert_eq!(llrb.first(), tree234.first());
+            assert_eq!(llrb.last(), tree234.last());
+            // 赤黒木の高さは2 log2(n + 1)以下.
+            assert!(llrb.height() <= 2 * (usize::BITS - llrb.len().leading_zeros()) as usize);
+
+            let (l, r) = (rng.below(keys), rng.below(keys));
+            for bound in [Bound::Included(&l), Bound::Excluded(&l), Bound::Unbounded] {
+                assert_eq!(llrb.lower_bound(bound), tree234.lower_bound(bound));
+                assert_eq!(llrb.upper_bound(bound), tree234.upper_bound(bound));
+            }
+            assert!(llrb.range(l..=r).eq(tree234.range(l..=r)), "step {step}");
+            assert!(llrb.range(l..r).eq(tree234.range(l..r)), "step {step}");
+            assert!(llrb.range(l..).eq(tree234.range(l..)), "step {step}");
+        });
+    }
+
+    #[test]
+    fn test_differential() {
+        differential(5000, 16, 1);
+        differential(5000, 1000, 2);
+        differential(5000, 1 << 20, 3);
+    }
+
+    #[test]
+    fn test_sequential() {
+        let mut tree = LLRBTree::new();
+        for i in 0..1000 {
+            tree.insert(i);
+        }
+        assert_eq!(tree.validate(), Ok(()));
+        // 2-3木としての高さはlog2(1000) + 1以下.
+        assert!(tree.black_height() <= 10);
+        for i in (0..1000).step_by(2) {
+            assert!(tree.delete(&i));
+        }
+        assert!(!tree.delete(&0));
+        assert_eq!(tree.validate(), Ok(()));
+        assert!(tree.iter().copied().eq((1..1000).step_by(2)));
+        while tree.pop_last().is_some() {
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        assert!(tree.is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::llrb::*;
+    use super::super::super::ordered_set::*;
+    use super::super::super::rbtree::*;
+    use super::super::super::tree234::*;
+    use super::super::random;
+    use std::collections::BTreeSet;
+
+    /// apply random operations to an OrderedSet through the trait only, and return a trace of the results.
+    fn trace<S: OrderedSet<u64> + Default>(steps: usize, keys: u64) -> Vec<u64> {
+        let mut set = S::default();
+        let mut trace = vec![];
+        random::differential(1, steps, keys, |_, key, rng| {
+            let result = match rng.below(3) {
+                0 | 1 => set.insert(key),
+                _ => set.remove(&key),
+            };
+            trace.push(result as u64);
+            trace.push(set.contains(&rng.below(keys)) as u64);
+            trace.push(set.len() as u64);
+            trace.extend(set.first().into_iter().chain(set.last()).copied());
+            let (l, r) = rng.pair(keys);
+            trace.push(set.range(l..r).count() as u64);
+            trace.extend(set.range(l..=r).take(3).copied());
+            trace.push(set.iter().sum());
+        });
+        assert_eq!(set.is_empty(), set.len() == 0);
+        trace
+    }
+
+    #[test]
+    fn test_same_trace() {
+        for keys in [8, 100, 1 << 20] {
+            let expected = trace::<BTreeSet<u64>>(3000, keys);
+            assert_eq!(trace::<Tree234<u64>>(3000, keys), expected);
+            assert_eq!(trace::<BTree<u64, 16>>(3000, keys), expected);
+            assert_eq!(trace::<RBTree<u64>>(3000, keys), expected);
+            assert_eq!(trace::<LLRBTree<u64>>(3000, keys), expected);
+        }
+    }
+
+    #[test]
+    fn test_set_semantics() {
+        let mut tree = Tree234::new();
+        assert!(OrderedSet::insert(&mut tree, 1));
+        assert!(!OrderedSet::insert(&mut tree, 1));
+        assert_eq!(tree.len(), 1);
+        assert!(OrderedSet::remove(&mut tree, &1));
+        assert!(!OrderedSet::remove(&mut tree, &1));
+        // Vecからの構築は他の木と同じく重複を一つにまとめる.
+        assert_eq!(OrderedSet::len(&RBTree::from(vec![2, 2, 3])), 2);
+        // 重複を許す挿入の後でも, removeは一つずつ消す.
+        let mut tree = RBTree::from(vec![2, 3]);
+        tree.insert(2);
+        assert!(OrderedSet::remove(&mut tree, &2));
+        assert!(OrderedSet::contains(&tree, &2));
+        assert_eq!(OrderedSet::len(&tree), 2);
+    }
+}
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::rbtree::*;
+    use super::super::super::tree234::*;
+    use super::super::random::{self, Model};
+
+    /// a RBTree against the model, checking the bound on its height.
+    fn differential(steps: usize, keys: u64, seed: u64) {
+        let mut tree = RBTree::new();
+        let mut model = Model::default();
+        random::differential(seed, steps, keys, |step, key, rng| {
+            match rng.below(8) {
+                0..=2 => {
+                    tree.insert(key);
+                    model.insert(key);
+                }
+                3 => assert_eq!(tree.insert_unique(key), model.insert_unique(key)),
+                _ => assert_eq!(tree.delete(&key), model.delete(key), "step {step}"),
+            }
+            assert_eq!(tree.validate(), Ok(()), "step {step}");
+            assert_eq!(tree.len(), model.len());
+            assert!(tree.iter().eq(model.iter()), "step {step}");
+            assert_eq!(tree.contains(&key), model.contains(&key));
+            assert_eq!(tree.first(), model.first());
+            assert_eq!(tree.last(), model.last());
+            // 赤黒木の高さは2 log2(n + 1)以下.
+            assert!(tree.height() <= 2 * (usize::BITS - model.len().leading_zeros()) as usize);
+        });
+    }
+
+    #[test]
+    fn test_differential() {
+        differential(5000, 32, 1);
+        differential(5000, 1 << 20, 2);
+    }
+
+    #[test]
+    fn test_sequential() {
+        let mut tree = RBTree::new();
+        for i in 0..1000 {
+            tree.insert(i);
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        assert!(tree.black_height() <= 10);
+        for i in (0..1000).step_by(2) {
+            assert!(tree.delete(&i));
+            assert!(!tree.delete(&i));
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        assert!(tree.iter().copied().eq((1..1000).step_by(2)));
+        while let Some(&first) = tree.first() {
+            assert!(tree.delete(&first));
+            assert_eq!(tree.validate(), Ok(()));
+        }
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut tree = RBTree::from(vec![2, 1, 3]);
+        assert_eq!(tree.validate(), Ok(()));
+        tree = RBTree::Node(Box::new(_RBTree::Red { data: 1, left: RBTree::Nil, right: RBTree::Nil, length: 1, aggregate: () }));
+        assert_eq!(tree.validate(), Err(RBInvariantError::RedRoot));
+        let red = |data| RBTree::Node(Box::new(_RBTree::Red { data, left: RBTree::Nil, right: RBTree::Nil, length: 1, aggregate: () }));
+        let black = |data, left, right, length| RBTree::Node(Box::new(_RBTree::Black { data, left, right, length, aggregate: () }));
+        assert_eq!(black(2, red(1), red(3), 3).validate(), Ok(()));
+        assert_eq!(black(2, red(3), RBTree::Nil, 2).validate(), Err(RBInvariantError::OutOfRange { depth: 1 }));
+        assert_eq!(black(2, red(1), RBTree::Nil, 3).validate(), Err(RBInvariantError::LengthMismatch { depth: 0, cached: 3, actual: 2 }));
+        assert_eq!(
+            black(2, black(1, RBTree::Nil, RBTree::Nil, 1), RBTree::Nil, 2).validate(),
+            Err(RBInvariantError::UnevenBlackHeight { expected: 2, found: 1 })
+        );
+        let red_red = RBTree::Node(Box::new(_RBTree::Red { data: 1, left: RBTree::Nil, right: red(2), length: 2, aggregate: () }));
+        assert_eq!(black(3, red_red, red(4), 4).validate(), Err(RBInvariantError::RedRed { depth: 1 }));
+    }
+
+    #[test]
+    fn test_from_tree234() {
+        let mut tree = Tree234::new();
+        random::differential(3, 2000, 64, |step, key, rng| {
+            if rng.below(3) == 0 {
+                tree.delete(&key);
+            } else {
+                tree.insert(key);
+            }
+            let rb = RBTree::from(tree.clone());
+            assert_eq!(rb.validate(), Ok(()), "step {step}");
+            assert!(rb.iter().eq(tree.iter()));
+            assert_eq!(rb.len(), tree.len());
+            // 黒の高さは2-3-4木の高さと同じで, 赤を含めると高々2倍になる.
+            assert_eq!(rb.black_height(), tree.height());
+            assert!(rb.height() <= 2 * tree.height());
+            // 2-3-4木に戻すと元と同じ形になる.
+            assert!(Tree234::from(rb) == tree, "step {step}");
+        });
+    }
+
+    #[test]
+    fn test_into_tree234() {
+        let mut rb = RBTree::new();
+        random::differential(4, 2000, 64, |step, key, rng| {
+            if rng.below(3) == 0 {
+                rb.delete(&key);
+            } else {
+                rb.insert(key);
+            }
+            let black_height = rb.black_height();
+            let tree = Tree234::from(rb.clone());
+            assert_eq!(tree.validate(), Ok(()), "step {step}");
+            assert!(tree.iter().eq(rb.iter()));
+            assert_eq!(tree.len(), rb.len());
+            assert_eq!(tree.height(), black_height);
+            let back = RBTree::from(tree);
+            assert!(back.iter().eq(rb.iter()));
+            assert_eq!(back.black_height(), black_height);
+        });
+        assert!(RBTree::from(Tree234::<u64>::new()).is_empty());
+        assert!(Tree234::from(RBTree::<u64>::new()).is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::super::super::rbtree::*;
+    use super::super::super::tree234::*;
+    use super::super::super::tree234_monoid::*;
+    use super::super::super::union_find::*;
+    use serde_test::{assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token};
+
+    #[test]
+    fn test_tree234_tokens() {
+        let tree = Tree234::from_sorted_iter(vec![1, 2, 2, 5]);
+        assert_tokens(
+            &tree,
+            &[Token::Seq { len: Some(4) }, Token::I32(1), Token::I32(2), Token::I32(2), Token::I32(5), Token::SeqEnd],
+        );
+        assert_tokens(&Tree234::<i32>::new(), &[Token::Seq { len: Some(0) }, Token::SeqEnd]);
+        assert_de_tokens_error::<Tree234<i32>>(
+            &[Token::Seq { len: Some(2) }, Token::I32(2), Token::I32(1), Token::SeqEnd],
+            "values should be sorted in ascending order",
+        );
+    }
+
+    #[test]
+    fn test_tree234_round_trip() {
+        let tree: BTree<u64, 6, Sum> = BTree::from((0..1000).map(|i| i * 37 % 1000).collect::<Vec<_>>());
+        let json = serde_json::to_string(&tree).unwrap();
+        let restored: BTree<u64, 6, Sum> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.validate(), Ok(()));
+        assert!(restored.iter().eq(tree.iter()));
+        assert_eq!(restored.fold(..), tree.fold(..));
+        assert_eq!(restored.fold(100..200), tree.fold(100..200));
+    }
+
+    #[test]
+    fn test_rbtree_round_trip() {
+        let tree = RBTree::from(vec![5, 3, 8, 3, 1]);
+        assert_ser_tokens(
+            &tree,
+            &[
+                Token::Seq { len: Some(4) },
+                Token::I32(1),
+                Token::I32(3),
+                Token::I32(5),
+                Token::I32(8),
+                Token::SeqEnd,
+            ],
+        );
+        // Tree234と同じく, 重複は残し, 昇順でない列は読まない.
+        let restored: RBTree<i32> = serde_json::from_str("[2, 2, 4, 9]").unwrap();
+        assert_eq!(restored.validate(), Ok(()));
+        assert_eq!(restored.iter().collect::<Vec<_>>(), vec![&2, &2, &4, &9]);
+        assert_de_tokens_error::<RBTree<i32>>(
+            &[Token::Seq { len: Some(2) }, Token::I32(2), Token::I32(1), Token::SeqEnd],
+            "values should be sorted in ascending order",
+        );
+        // まとめて作った木も赤黒木の条件を満たし, 長さと集約値を持つ.
+        for n in 0..100u64 {
+            let json = serde_json::to_string(&(0..n).collect::<Vec<_>>()).unwrap();
+            let restored: AugmentedRBTree<u64, Sum> = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.validate(), Ok(()));
+            assert_eq!(restored.len(), n as usize);
+            assert_eq!(restored.aggregate(), n * n.saturating_sub(1) / 2);
+            assert_eq!(restored.fold(10..20), (10..20.min(n)).sum::<u64>());
+        }
+    }
+
+    #[test]
+    fn test_union_find_round_trip() {
+        let mut uf = UnionFind::new(6);
+        uf.unite(0, 1);
+        uf.unite(2, 3);
+        uf.unite(1, 3);
+        let json = serde_json::to_string(&uf).unwrap();
+        let mut restored: UnionFind = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.parent, uf.parent);
+        assert_eq!(restored.size, uf.size);
+        assert_eq!(restored.size(0), 4);
+        assert_eq!(restored.size(5), 1);
+        for x in 0..6 {
+            for y in 0..6 {
+                assert_eq!(restored.same(x, y), uf.same(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_union_find_invalid() {
+        let error = serde_json::from_str::<UnionFind>("[[0, 5, 1], [1, 1, 1]]").map(|_| ()).unwrap_err();
+        assert!(error.to_string().contains("parent[1] = 5 is out of range for 3 elements"));
+        let error = serde_json::from_str::<UnionFind>("[[0, 2, 3, 1], [1, 1, 1, 1]]").map(|_| ()).unwrap_err();
+        assert!(error.to_string().contains("is on a cycle of parents"));
+        let error = serde_json::from_str::<UnionFind>("[[1, 1, 2], [1, 2]]").map(|_| ()).unwrap_err();
+        assert!(error.to_string().contains("2 sizes are given for 3 elements"));
+        let error = serde_json::from_str::<UnionFind>("[[1, 1, 1], [1, 2, 1]]").map(|_| ()).unwrap_err();
+        assert!(error.to_string().contains("size[1] = 2 but 3 elements have the root 1"));
+        // 根でない要素の大きさは使わないので, 何が入っていてもよい.
+        assert!(serde_json::from_str::<UnionFind>("[[1, 1, 2], [7, 2, 1]]").is_ok());
+        assert!(serde_json::from_str::<UnionFind>("[[], []]").is_ok());
+    }
+
+    #[test]
+    fn test_graph_round_trip() {
+        // グラフは隣接リストの Vec なので, serde の標準の実装で読み書きできる.
+        let graph: Vec<Vec<(usize, usize)>> = vec![vec![(1, 4), (2, 1)], vec![], vec![(1, 2)]];
+        let json = serde_json::to_string(&graph).unwrap();
+        assert_eq!(json, "[[[1,4],[2,1]],[],[[1,2]]]");
+        let restored: Vec<Vec<(usize, usize)>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(super::super::super::dijkstra::dijkstra(&restored, 0), vec![0, 3, 1]);
+    }
+}
@@ -1,47 +1,41 @@
 #[cfg(test)]
 mod tests {
+    use super::super::super::array_vec::*;
     use super::super::super::tree234::*;
+    use super::super::super::tree234_validate::*;
+    use super::super::random;
+    use std::cell::{Cell, RefCell};
+    use std::collections::BTreeSet;
     use std::fmt::Debug;
+    use std::ops::Bound;
+    use std::rc::Rc;
 
 
     #[test]
     fn test_new() {
         let tree = Tree234::<usize>::new();
-        assert_eq!(tree.size, 0);
-        assert_eq!(tree.data, [None, None, None, None]);
-        assert_eq!(tree.children, [None, None, None, None]);
+        assert_eq!(tree.size(), 0);
+        assert!(tree.data.is_empty());
+        assert!(tree.children.is_empty());
+        assert_eq!(tree.len(), 0);
     }
 
     #[test]
     fn test_clear() {
-        let mut tree = Tree234::<usize>::new();
-        tree.size = 3;
-        tree.data = [Some(1), Some(2), Some(3), None];
-        tree.children = [
-            Some(Box::new(Tree234::<usize> {
-                data: [Some(4), Some(5), None, None],
-                children: [None, None, None, None],
-                size: 2,
-            })),
-            Some(Box::new(Tree234::<usize> {
-                data: [Some(6), Some(7), None, None],
-                children: [None, None, None, None],
-                size: 2,
-            })),
-            None,
-            None,
-        ];
+        let mut tree: Tree234<usize> = "[[1 2] 3 [4 5] 6 [7]]".parse().unwrap();
+        assert_eq!(tree.len(), 7);
         tree.clear();
-        assert_eq!(tree.size, 0);
-        assert_eq!(tree.data, [None, None, None, None]);
-        assert_eq!(tree.children, [None, None, None, None]);
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.len(), 0);
+        assert!(tree.data.is_empty());
+        assert!(tree.children.is_empty());
     }
 
     #[test]
     fn test_is_empty() {
         let mut tree = Tree234::<usize>::new();
         assert!(tree.is_empty());
-        tree.size = 1;
+        tree.data.push(1);
         assert!(!tree.is_empty());
     }
 
@@ -49,27 +43,21 @@ mod tests {
     fn test_insert() {
         let mut tree = Tree234::<usize>::new();
         tree.insert(1);
-        assert_eq!(tree.size, 1);
-        assert_eq!(tree.data, [Some(1), None, None, None]);
+        assert_eq!(tree.size(), 1);
+        assert_eq!(values(&tree), vec![1]);
         tree.insert(2);
-        assert_eq!(tree.size, 2);
-        assert_eq!(tree.data, [Some(1), Some(2), None, None]);
+        assert_eq!(tree.size(), 2);
+        assert_eq!(values(&tree), vec![1, 2]);
         tree.insert(3);
-        assert_eq!(tree.size, 3);
-        assert_eq!(tree.data, [Some(1), Some(2), Some(3), None]);
+        assert_eq!(tree.size(), 3);
+        assert_eq!(values(&tree), vec![1, 2, 3]);
         tree.insert(4);
-        assert_eq!(tree.size, 1);
-        assert_eq!(tree.data, [Some(2), None, None, None]);
-        assert_eq!(
-            tree.children[0].as_ref().unwrap().data,
-            [Some(1), None, None, None]
-        );
-        assert_eq!(tree.children[0].as_ref().unwrap().size, 1);
-        assert_eq!(
-            tree.children[1].as_ref().unwrap().data,
-            [Some(3), Some(4), None, None]
-        );
-        assert_eq!(tree.children[1].as_ref().unwrap().size, 2);
+        assert_eq!(tree.size(), 1);
+        assert_eq!(values(&tree), vec![2]);
+        assert_eq!(values(&tree.children[0]), vec![1]);
+        assert_eq!(tree.children[0].size(), 1);
+        assert_eq!(values(&tree.children[1]), vec![3, 4]);
+        assert_eq!(tree.children[1].size(), 2);
         tree.clear();
         for i in vec![1, 2, 3, 4, 5, 6, 7, 8, 9] {
             tree.insert(i);
@@ -120,49 +108,586 @@ mod tests {
         check(&tree);
     }
 
-    fn first_data<T: Clone>(tree: &Option<Box<Tree234<T>>>) -> T {
-        let tree = tree.as_ref().unwrap();
-        let tree = tree.as_ref();
-        tree.data[0].clone().unwrap()
+    #[test]
+    fn test_delete_shapes() {
+        // 削除の各場合について, 削除前後の形を正確に確かめる.
+        let cases = [
+            // 右の兄弟から左回転で借りる.
+            ("[[1] 3 [5 6 7]]", 1, "[[3] 5 [6 7]]"),
+            // 左の兄弟から右回転で借りる.
+            ("[[1 2] 3 [4]]", 4, "[[1] 2 [3]]"),
+            // 兄弟に余裕がないので親の値と併合する.
+            ("[[1] 2 [3] 4 [5 6 7]]", 2, "[[1 3] 4 [5 6 7]]"),
+            // 根が1つの値しか持たないので高さを下げる.
+            ("[[3] 5 [6]]", 6, "[3 5]"),
+            // 内部ノードの値を左の部分木の最大値で置き換える.
+            ("[[1 3] 4 [5 6 7]]", 4, "[[1] 3 [5 6 7]]"),
+            // 回転で孫ごと移した後, その下で併合する.
+            ("[[[1] 2 [3]] 4 [[5] 6 [7] 8 [9]]]", 1, "[[[2 3] 4 [5]] 6 [[7] 8 [9]]]"),
+        ];
+        for (before, value, after) in cases {
+            let mut tree: Tree234<usize> = before.parse().unwrap();
+            assert!(tree.delete(&value), "{before}");
+            assert_eq!(tree.to_shape(), after, "delete {value} from {before}");
+            check(&tree);
+        }
+    }
+
+    #[test]
+    fn test_delete_all() {
+        let values = vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90];
+        let mut tree = Tree234::from(values.clone());
+        for (i, value) in values.iter().enumerate() {
+            assert!(tree.delete(value));
+            check(&tree);
+            assert!(!tree.find(value));
+            assert!(values[i + 1..].iter().all(|x| tree.find(x)));
+        }
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut tree = Tree234::<usize>::new();
+        assert_eq!(tree.iter().next(), None);
+        tree.append(vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90]);
+        let mut expected = vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90];
+        expected.sort();
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), expected);
+        assert_eq!((&tree).into_iter().count(), expected.len());
+    }
+
+    #[test]
+    fn test_range() {
+        let tree = Tree234::from((0..30).map(|x| x * 2).collect::<Vec<usize>>());
+        assert_eq!(tree.range(10..16).copied().collect::<Vec<_>>(), vec![10, 12, 14]);
+        assert_eq!(tree.range(9..=16).copied().collect::<Vec<_>>(), vec![10, 12, 14, 16]);
+        assert_eq!(tree.range(..5).copied().collect::<Vec<_>>(), vec![0, 2, 4]);
+        assert_eq!(tree.range(55..).copied().collect::<Vec<_>>(), vec![56, 58]);
+        assert_eq!(
+            tree.range((Bound::Excluded(10), Bound::Excluded(16))).copied().collect::<Vec<_>>(),
+            vec![12, 14]
+        );
+        assert_eq!(tree.range(..).count(), 30);
+        assert_eq!(tree.range(11..12).next(), None);
+        assert_eq!(tree.range(60..).next(), None);
+        assert_eq!(tree.range((Bound::Included(20), Bound::Excluded(10))).next(), None);
     }
 
-    fn last_data<T: Clone>(tree: &Option<Box<Tree234<T>>>) -> T {
-        let tree = tree.as_ref().unwrap();
-        let tree = tree.as_ref();
-        tree.data[tree.size - 1].clone().unwrap()
+    #[test]
+    fn test_bounds() {
+        let mut tree = Tree234::<usize>::new();
+        assert_eq!(tree.lower_bound(Bound::Unbounded), None);
+        assert_eq!(tree.upper_bound(Bound::Included(&1)), None);
+        tree.append(vec![10, 20, 30, 40, 50, 60, 70]);
+        assert_eq!(tree.lower_bound(Bound::Included(&30)), Some(&30));
+        assert_eq!(tree.lower_bound(Bound::Excluded(&30)), Some(&40));
+        assert_eq!(tree.lower_bound(Bound::Included(&31)), Some(&40));
+        assert_eq!(tree.lower_bound(Bound::Excluded(&70)), None);
+        assert_eq!(tree.upper_bound(Bound::Included(&30)), Some(&30));
+        assert_eq!(tree.upper_bound(Bound::Excluded(&30)), Some(&20));
+        assert_eq!(tree.upper_bound(Bound::Included(&29)), Some(&20));
+        assert_eq!(tree.upper_bound(Bound::Excluded(&10)), None);
+        assert_eq!(tree.predecessor(&40), Some(&30));
+        assert_eq!(tree.predecessor(&10), None);
+        assert_eq!(tree.successor(&40), Some(&50));
+        assert_eq!(tree.successor(&70), None);
+        assert_eq!(tree.search_and_get_range(&35), (Some(&30), Some(&40)));
+        assert_eq!(tree.search_and_get_range(&40), (Some(&40), Some(&40)));
+        assert_eq!(tree.search_and_get_range(&5), (None, Some(&10)));
+        assert_eq!(tree.search_and_get_range(&75), (Some(&70), None));
+    }
+
+    #[test]
+    fn test_first_last() {
+        let mut tree = Tree234::<usize>::new();
+        assert_eq!(tree.first(), None);
+        assert_eq!(tree.last(), None);
+        assert_eq!(tree.pop_first(), None);
+        assert_eq!(tree.pop_last(), None);
+        tree.append(vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90]);
+        assert_eq!(tree.first(), Some(&5));
+        assert_eq!(tree.last(), Some(&98));
+        assert_eq!(tree.pop_first(), Some(5));
+        check(&tree);
+        assert_eq!(tree.pop_last(), Some(98));
+        check(&tree);
+        assert_eq!(tree.first(), Some(&13));
+        assert_eq!(tree.last(), Some(&96));
+        let mut popped = vec![];
+        while let Some(value) = tree.pop_first() {
+            check(&tree);
+            popped.push(value);
+        }
+        assert!(popped.windows(2).all(|x| x[0] < x[1]));
+        assert_eq!(popped.len(), 25);
+    }
+
+    #[test]
+    fn test_len() {
+        let mut tree = Tree234::<usize>::new();
+        for i in 0..100 {
+            assert_eq!(tree.len(), i);
+            tree.insert(i * 7 % 100);
+        }
+        assert_eq!(tree.len(), 100);
+        check(&tree);
+        for i in 0..50 {
+            tree.delete(&(i * 2));
+            assert_eq!(tree.len(), 99 - i);
+        }
+        check(&tree);
+        tree.pop_first();
+        tree.pop_last();
+        assert_eq!(tree.len(), 48);
+        tree.delete(&1000);
+        assert_eq!(tree.len(), 48);
+        check(&tree);
+    }
+
+    #[test]
+    fn test_nth_rank() {
+        let values = vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90];
+        let mut sorted = values.clone();
+        sorted.sort();
+        let tree = Tree234::from(values);
+        for (k, value) in sorted.iter().enumerate() {
+            assert_eq!(tree.nth(k), Some(value));
+            assert_eq!(tree.rank(value), k);
+            assert_eq!(tree.rank(&(value + 1)), k + 1);
+        }
+        assert_eq!(tree.nth(sorted.len()), None);
+        assert_eq!(tree.rank(&0), 0);
+        assert_eq!(tree.rank(&1000), sorted.len());
+    }
+
+    #[test]
+    fn test_count_range() {
+        let tree = Tree234::from((0..30).map(|x| x * 2).collect::<Vec<usize>>());
+        assert_eq!(tree.count_range(10..16), 3);
+        assert_eq!(tree.count_range(9..=16), 4);
+        assert_eq!(tree.count_range(..5), 3);
+        assert_eq!(tree.count_range(55..), 2);
+        assert_eq!(tree.count_range((Bound::Excluded(10), Bound::Excluded(16))), 2);
+        assert_eq!(tree.count_range(..), 30);
+        assert_eq!(tree.count_range(11..12), 0);
+        assert_eq!(tree.count_range((Bound::Included(20), Bound::Excluded(10))), 0);
+        assert_eq!(Tree234::<usize>::new().count_range(..), 0);
+    }
+
+    #[test]
+    fn test_multiset() {
+        let mut tree = Tree234::<usize>::new();
+        for i in 0..60 {
+            tree.insert(i % 4);
+            check(&tree);
+        }
+        assert_eq!(tree.len(), 60);
+        for i in 0..4 {
+            assert_eq!(tree.count(&i), 15);
+        }
+        assert_eq!(tree.count(&4), 0);
+        assert_eq!(tree.rank(&2), 30);
+        assert_eq!(tree.range(1..3).count(), 30);
+        assert!(tree.iter().copied().eq((0..4).flat_map(|i| vec![i; 15])));
+        assert!(tree.delete_one(&1));
+        check(&tree);
+        assert_eq!(tree.count(&1), 14);
+        assert_eq!(tree.delete_all(&2), 15);
+        check(&tree);
+        assert_eq!(tree.count(&2), 0);
+        assert!(!tree.find(&2));
+        assert_eq!(tree.delete_all(&2), 0);
+        assert_eq!(tree.len(), 44);
+        assert_eq!(tree.delete_all(&0), 15);
+        assert_eq!(tree.delete_all(&3), 15);
+        assert_eq!(tree.delete_all(&1), 14);
+        assert!(tree.is_empty());
+        // the separator pulled up by a split must stay next to its equal values
+        for i in [0, 0, 0, 0, 1, 2] {
+            tree.insert(i);
+            check(&tree);
+        }
+        assert!(tree.iter().copied().eq(vec![0, 0, 0, 0, 1, 2]));
+        // 重複が複数のノードにまたがる場合も, 切り離してつなげ直す.
+        let mut tree = Tree234::from_sorted_iter((0..300).flat_map(|i| vec![i; 5]));
+        for value in [150, 0, 299] {
+            assert_eq!(tree.delete_all(&value), 5);
+            check(&tree);
+        }
+        assert_eq!(tree.len(), 1485);
+        assert_eq!(tree.first(), Some(&1));
+        assert_eq!(tree.last(), Some(&298));
+        assert_eq!(tree.rank(&151), 745);
+    }
+
+    #[test]
+    fn test_insert_unique() {
+        let mut tree = Tree234::<usize>::new();
+        for i in 0..60 {
+            assert_eq!(tree.insert_unique(i % 20), i < 20);
+            check(&tree);
+        }
+        assert_eq!(tree.len(), 20);
+        assert!((0..20).all(|i| tree.count(&i) == 1));
+        assert!(tree.delete_one(&5));
+        assert!(!tree.find(&5));
+        assert_eq!(tree.delete_all(&6), 1);
+        assert!(tree.insert_unique(5));
+        assert_eq!(tree.len(), 19);
+    }
+
+    #[test]
+    fn test_height() {
+        let mut tree = Tree234::<usize>::new();
+        assert_eq!(tree.height(), 0);
+        tree.append(vec![1, 2, 3]);
+        assert_eq!(tree.height(), 1);
+        tree.insert(4);
+        assert_eq!(tree.height(), 2);
+        tree.append((5..1000).collect());
+        assert!(tree.height() <= 10);
+        assert_eq!(tree.height(), check_depth(&tree));
+    }
+
+    #[test]
+    fn test_split_off() {
+        let values = vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90];
+        let mut sorted = values.clone();
+        sorted.sort();
+        for key in [0, 5, 30, 31, 60, 98, 99] {
+            let mut left = Tree234::from(values.clone());
+            let right = left.split_off(&key);
+            check(&left);
+            check(&right);
+            assert!(left.iter().copied().eq(sorted.iter().copied().filter(|&x| x < key)));
+            assert!(right.iter().copied().eq(sorted.iter().copied().filter(|&x| x >= key)));
+        }
+        let mut tree = Tree234::from_sorted_iter(vec![1, 2, 2, 2, 3]);
+        let right = tree.split_off(&2);
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec![1]);
+        assert_eq!(right.iter().copied().collect::<Vec<_>>(), vec![2, 2, 2, 3]);
+        let mut empty = Tree234::<usize>::new();
+        assert!(empty.split_off(&1).is_empty());
+    }
+
+    #[test]
+    fn test_join() {
+        for (n, m) in [(0, 0), (0, 5), (5, 0), (1, 1), (3, 100), (100, 3), (50, 60), (500, 2)] {
+            let left = Tree234::from((0..n).collect::<Vec<usize>>());
+            let right = Tree234::from((n..n + m).collect::<Vec<usize>>());
+            let tree = Tree234::join(left, right);
+            check(&tree);
+            assert!(tree.iter().copied().eq(0..n + m));
+        }
+        let mut tree = Tree234::from_sorted_iter(vec![1, 2, 2]);
+        tree.append_tree(Tree234::from_sorted_iter(vec![2, 3]));
+        check(&tree);
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec![1, 2, 2, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_join_unordered() {
+        Tree234::join(Tree234::from(vec![1, 5]), Tree234::from(vec![3, 4]));
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = Tree234::from(vec![1, 3, 5, 7, 9, 11]);
+        let b = Tree234::from(vec![3, 4, 5, 6, 7]);
+        assert_eq!(a.union(&b).copied().collect::<Vec<usize>>(), vec![1, 3, 4, 5, 6, 7, 9, 11]);
+        assert_eq!(a.intersection(&b).copied().collect::<Vec<_>>(), vec![3, 5, 7]);
+        assert_eq!(a.difference(&b).copied().collect::<Vec<_>>(), vec![1, 9, 11]);
+        assert_eq!(b.difference(&a).copied().collect::<Vec<_>>(), vec![4, 6]);
+        assert_eq!(a.symmetric_difference(&b).copied().collect::<Vec<_>>(), vec![1, 4, 6, 9, 11]);
+        let empty = Tree234::new();
+        assert!(a.union(&empty).eq(a.iter()));
+        assert_eq!(a.intersection(&empty).next(), None);
+        assert!(empty.is_subset(&a));
+        assert!(a.is_superset(&empty));
+        assert!(!a.is_subset(&b));
+        assert!(Tree234::from(vec![3, 5]).is_subset(&b));
+        assert!(!a.is_disjoint(&b));
+        assert!(a.is_disjoint(&Tree234::from(vec![0, 2, 4])));
+        // duplicates behave as a multiset
+        let c = Tree234::from_sorted_iter(vec![1, 1, 1, 2]);
+        let d = Tree234::from_sorted_iter(vec![1, 2, 2]);
+        assert_eq!(c.union(&d).copied().collect::<Vec<usize>>(), vec![1, 1, 1, 2, 2]);
+        assert_eq!(c.intersection(&d).copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(c.difference(&d).copied().collect::<Vec<_>>(), vec![1, 1]);
+        assert_eq!(c.symmetric_difference(&d).copied().collect::<Vec<_>>(), vec![1, 1, 2]);
+        assert!(!Tree234::from_sorted_iter(vec![1, 1]).is_subset(&d));
+    }
+
+    #[test]
+    fn test_union_with() {
+        let mut a = Tree234::from((0..300).step_by(3).collect::<Vec<usize>>());
+        a.union_with(Tree234::from((0..300).step_by(2).collect::<Vec<_>>()));
+        check(&a);
+        assert!(a.iter().copied().eq((0..300).filter(|x| x % 2 == 0 || x % 3 == 0)));
+        let mut b = Tree234::from((1000..2000).collect::<Vec<usize>>());
+        b.union_with(Tree234::from((0..500).collect::<Vec<_>>()));
+        check(&b);
+        assert!(b.iter().copied().eq((0..500).chain(1000..2000)));
+        let mut c = Tree234::from_sorted_iter(vec![1, 1, 2]);
+        c.union_with(Tree234::from_sorted_iter(vec![1, 2, 2, 3]));
+        check(&c);
+        assert_eq!(c.iter().copied().collect::<Vec<usize>>(), vec![1, 1, 2, 2, 3]);
+        let mut d = Tree234::new();
+        d.union_with(Tree234::from(vec![1, 2]));
+        d.union_with(Tree234::new());
+        assert_eq!(d.iter().copied().collect::<Vec<usize>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        for n in 0..300 {
+            let tree = Tree234::from_sorted_iter(0..n);
+            check(&tree);
+            check_nodes(&tree);
+            assert_eq!(tree.len(), n);
+            assert!(tree.iter().copied().eq(0..n));
+            assert!(tree.height() <= (n + 1).next_power_of_two().trailing_zeros() as usize);
+        }
+        let tree = Tree234::from_sorted_iter(vec![1, 1, 2, 2, 2, 3]);
+        check(&tree);
+        assert_eq!(tree.count(&2), 3);
+        let tree = Tree234::from(vec![3, 1, 2, 3, 1]);
+        check(&tree);
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_sorted_iter_unsorted() {
+        Tree234::from_sorted_iter(vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut tree = Tree234::<usize>::new();
+        tree.append((0..100).rev().collect());
+        check(&tree);
+        tree.append((100..200).collect());
+        check(&tree);
+        assert!(tree.iter().copied().eq(0..200));
+        tree.append(vec![50, 150, 250]);
+        check(&tree);
+        assert_eq!(tree.len(), 203);
+        assert_eq!(tree.count(&50), 2);
+        assert_eq!(tree.last(), Some(&250));
+    }
+
+    fn leaf(data: Vec<usize>) -> Box<Tree234<usize>> {
+        let mut node = Tree234::new();
+        node.length = data.len();
+        node.data = ArrayVec::from_iter(data);
+        Box::new(node)
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Tree234::<usize>::new().validate(), Ok(()));
+        let valid: Tree234<usize> = "[[1 2] 5 [6]]".parse().unwrap();
+        assert_eq!(valid.validate(), Ok(()));
+
+        let mut tree = valid.clone();
+        tree.children[1] = leaf(vec![]);
+        assert_eq!(tree.validate(), Err(InvariantError::NodeSize { depth: 1, size: 0 }));
+
+        let mut tree = valid.clone();
+        tree.children[0] = leaf(vec![2, 1]);
+        assert_eq!(tree.validate(), Err(InvariantError::UnsortedNode { depth: 1, index: 0 }));
+
+        let mut tree = valid.clone();
+        tree.children[1] = leaf(vec![4]);
+        assert_eq!(tree.validate(), Err(InvariantError::OutOfRange { depth: 1, index: 0 }));
+
+        let mut tree = valid.clone();
+        tree.children.push(leaf(vec![7]));
+        assert_eq!(tree.validate(), Err(InvariantError::UnexpectedChild { depth: 0, index: 2 }));
+
+        let mut tree = valid.clone();
+        tree.children.pop();
+        assert_eq!(tree.validate(), Err(InvariantError::MissingChild { depth: 0, index: 1 }));
+
+        let mut tree = valid.clone();
+        tree.length = 5;
+        assert_eq!(
+            tree.validate(),
+            Err(InvariantError::LengthMismatch { depth: 0, cached: 5, actual: 4 })
+        );
+
+        let mut tree = valid.clone();
+        *tree.children[1] = "[[6] 7 [8]]".parse().unwrap();
+        tree.length = 6;
+        assert_eq!(tree.validate(), Err(InvariantError::UnevenDepth { expected: 1, found: 2 }));
+        assert_eq!(
+            tree.validate().unwrap_err().to_string(),
+            "a leaf is at depth 2 while another leaf is at depth 1"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "debug-invariants")]
+    #[should_panic(expected = "Tree234 invariant violated")]
+    fn test_debug_invariants() {
+        let mut tree = Tree234::from((0..10).collect::<Vec<usize>>());
+        tree.child_mut(0).length += 1;
+        tree.insert(10);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-invariants")]
+    #[should_panic(expected = "Tree234 invariant violated: data[")]
+    fn test_debug_invariants_pop() {
+        let mut tree = Tree234::from((0..10).collect::<Vec<usize>>());
+        // 形はそのままで, 右端の葉の順序だけを壊す.
+        let mut node = &mut tree;
+        while !node.is_leaf() {
+            let size = node.size();
+            node = node.child_mut(size);
+        }
+        let last = node.size() - 1;
+        node.data[last] = 0;
+        tree.pop_first();
+    }
+
+    /// records which values are alive, to find leaks and double drops.
+    #[derive(Default)]
+    struct Tracker {
+        next_id: Cell<usize>,
+        live: RefCell<BTreeSet<usize>>,
+    }
+
+    impl Tracker {
+        fn value(self: &Rc<Self>, key: usize) -> Tracked {
+            let id = self.next_id.get();
+            self.next_id.set(id + 1);
+            self.live.borrow_mut().insert(id);
+            Tracked { key, id, tracker: self.clone() }
+        }
+
+        fn live(&self) -> usize {
+            self.live.borrow().len()
+        }
+    }
+
+    /// a value ordered by key, which tells its tracker when it is dropped.
+    struct Tracked {
+        key: usize,
+        id: usize,
+        tracker: Rc<Tracker>,
+    }
+
+    impl Clone for Tracked {
+        fn clone(&self) -> Self {
+            self.tracker.value(self.key)
+        }
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            assert!(self.tracker.live.borrow_mut().remove(&self.id), "value {} was dropped twice", self.id);
+        }
+    }
+
+    impl PartialEq for Tracked {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Tracked {}
+
+    impl PartialOrd for Tracked {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Tracked {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        let tracker = Rc::new(Tracker::default());
+        {
+            let mut tree = Tree234::new();
+            random::differential(1, 3000, 50, |step, key, _| {
+                let key = key as usize;
+                match step % 6 {
+                    0 | 1 => tree.insert(tracker.value(key)),
+                    2 => {
+                        tree.delete(&tracker.value(key));
+                    }
+                    3 => {
+                        tree.pop_first();
+                    }
+                    4 => {
+                        tree.pop_last();
+                    }
+                    _ => {
+                        let right = tree.split_off(&tracker.value(key));
+                        tree.append_tree(right);
+                    }
+                }
+                assert_eq!(tracker.live(), tree.len());
+                assert_eq!(tree.validate(), Ok(()));
+            });
+            tree.union_with(Tree234::from_sorted_iter((0..100).map(|key| tracker.value(key))));
+            tree.append((0..50).map(|key| tracker.value(key)).collect());
+            assert_eq!(tree.delete_all(&tracker.value(10)), 2);
+            assert_eq!(tracker.live(), tree.len());
+            let cloned = tree.clone();
+            assert_eq!(tracker.live(), tree.len() * 2);
+            drop(cloned);
+            tree.clear();
+            assert_eq!(tracker.live(), 0);
+            tree.append((0..100).map(|key| tracker.value(key % 7)).collect());
+            assert_eq!(tracker.live(), 100);
+        }
+        assert_eq!(tracker.live(), 0);
+    }
+
+    fn values<T: Clone>(tree: &Tree234<T>) -> Vec<T> {
+        tree.data.iter().cloned().collect()
     }
 
     fn check<T: Ord + Debug + Clone>(tree: &Tree234<T>) {
-        // if self.data.size == self.size && self.children.size == self.size + 1 && self.data.take(self.size) is all Some
-        assert!(tree.size <= 3);
-        assert_eq!(tree.size, tree.data.iter().filter(|x| x.is_some()).count());
-        assert!(tree.data.iter().take(tree.size).all(|x| x.is_some()));
-        if tree.is_leaf() {
-            assert!(tree.children.iter().all(|x| x.is_none()));
-        } else {
-            assert_eq!(
-                tree.size + 1,
-                tree.children.iter().filter(|x| x.is_some()).count()
-            );
-            assert!(tree
-                .children
-                .iter()
-                .take(tree.size + 1)
-                .all(|x| x.is_some()));
+        assert!(tree.size() <= 3);
+        assert_eq!(tree.len(), tree.iter().count());
+        assert_eq!(tree.validate(), Ok(()), "{:?}", tree);
+        if !tree.is_leaf() {
+            assert_eq!(tree.size() + 1, tree.children.len());
             // if self.children[0].data.last < self.data[0] < self.children[1].data.first < self.children[1].data.last < self.data[1] < ...
             let mut v = vec![];
-            for i in 0..tree.size {
-                v.push(first_data(&tree.children[i]));
-                v.push(last_data(&tree.children[i]));
-                v.push(tree.data[i].clone().unwrap());
+            for i in 0..tree.size() {
+                v.push(tree.child(i).first().unwrap().clone());
+                v.push(tree.child(i).last().unwrap().clone());
+                v.push(tree.key(i).clone());
             }
-            v.push(first_data(&tree.children[tree.size]));
+            v.push(tree.child(tree.size()).first().unwrap().clone());
             assert!(v.windows(2).all(|x| x[0] <= x[1]), "{:?}", tree);
             // all depth is equal
             check_depth(tree);
         }
     }
 
+    /// check every node, not only the root.
+    fn check_nodes<T: Ord + Debug + Clone>(tree: &Tree234<T>) {
+        check(tree);
+        assert!(tree.size() >= 1 || tree.is_leaf());
+        for child in tree.children.iter() {
+            assert!(child.size() >= 1);
+            check_nodes(child);
+        }
+    }
+
     fn check_depth<T: Debug>(tree: &Tree234<T>) -> usize {
         if tree.is_leaf() {
             return 1;
@@ -170,7 +695,7 @@ mod tests {
         let depths = tree
             .children
             .iter()
-            .filter_map(|x| x.as_ref().map(|x| check_depth(x)))
+            .map(|x| check_depth(x))
             .enumerate()
             .collect::<Vec<_>>();
         depths.windows(2).for_each(|x| assert_eq!(x[0].1, x[1].1, "{:?}, {:?}\n{:#?}", x[0], x[1], tree));
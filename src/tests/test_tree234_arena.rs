@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::super::tree234_arena::*;
+    use super::super::random;
+    use std::ops::Bound;
+
+    /// an ArenaBTree against a BTree of the same order, which serves as the model.
+    fn differential<const B: usize>(steps: usize, keys: u64) {
+        let mut arena = ArenaBTree::<u64, B>::new();
+        let mut boxed = BTree::<u64, B>::new();
+        random::differential(7, steps, keys, |step, key, rng| {
+            match rng.below(8) {
+                0..=2 => {
+                    arena.insert(key);
+                    boxed.insert(key);
+                }
+                3 | 4 => assert_eq!(arena.delete(&key), boxed.delete(&key)),
+                5 => assert_eq!(arena.pop_first(), boxed.pop_first()),
+                6 => assert_eq!(arena.pop_last(), boxed.pop_last()),
+                _ => {
+                    // BTreeを経由して往復しても値は変わらない.
+                    let converted = BTree::from(std::mem::take(&mut arena));
+                    assert_eq!(converted.validate(), Ok(()));
+                    arena = ArenaBTree::from(converted);
+                }
+            }
+            assert_eq!(arena.validate(), Ok(()), "B = {B}, step {step}");
+            assert_eq!(arena.len(), boxed.len());
+            assert!(arena.iter().eq(boxed.iter()), "B = {B}, step {step}");
+            assert_eq!(arena.find(&key), boxed.find(&key));
+            assert_eq!(arena.count(&key), boxed.count(&key));
+            assert_eq!(arena.rank(&key), boxed.rank(&key));
+            assert_eq!(arena.nth(key as usize), boxed.nth(key as usize));
+            assert_eq!(arena.predecessor(&key), boxed.predecessor(&key));
+            assert_eq!(arena.successor(&key), boxed.successor(&key));
+            assert!(arena.range(key / 2..key).eq(boxed.range(key / 2..key)));
+            assert!(arena.range((Bound::Excluded(key), Bound::Unbounded)).eq(boxed.range((Bound::Excluded(key), Bound::Unbounded))));
+        });
+    }
+
+    #[test]
+    fn test_differential() {
+        differential::<4>(3000, 64);
+        differential::<4>(3000, 10000);
+        differential::<8>(3000, 256);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let n = 10000;
+        let mut tree = ArenaTree234::with_capacity(n);
+        let capacity = tree.capacity();
+        assert!(capacity >= n);
+        for i in 0..n {
+            tree.insert(i * 7919 % n);
+        }
+        assert_eq!(tree.capacity(), capacity);
+        assert_eq!(tree.validate(), Ok(()));
+        tree.clear();
+        assert!(tree.is_empty());
+        assert_eq!(tree.first(), None);
+        assert_eq!(tree.capacity(), capacity);
+        tree.append((0..n).collect());
+        assert_eq!(tree.capacity(), capacity);
+        assert!(tree.iter().copied().eq(0..n));
+    }
+
+    #[test]
+    fn test_reuse() {
+        let mut tree = ArenaTree234::new();
+        for round in 0..5 {
+            for i in 0..1000 {
+                tree.insert(i * 31 % 1000);
+            }
+            let nodes = tree.nodes.len() - tree.free.len();
+            assert!(nodes <= 1000, "round {round}: {nodes} nodes");
+            for i in 0..1000 {
+                assert!(tree.delete(&i));
+            }
+            assert!(tree.is_empty());
+            // 削除で解放したノードは次の挿入で使い回す.
+            assert!(tree.nodes.len() <= 1000);
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        for n in [0, 1, 10, 100, 1000] {
+            let tree = ArenaTree234::from_sorted_iter((0..n).map(|i| i / 2));
+            let mut boxed = Tree234::from(tree);
+            assert_eq!(boxed.validate(), Ok(()));
+            assert!(boxed.iter().copied().eq((0..n).map(|i| i / 2)));
+            // 分割や集合演算はBTreeに移して行う.
+            let right = boxed.split_off(&(n / 4));
+            let (left, right) = (ArenaTree234::from(boxed), ArenaTree234::from(right));
+            assert_eq!(left.validate(), Ok(()));
+            assert_eq!(right.validate(), Ok(()));
+            assert!(left.iter().copied().eq((0..n).map(|i| i / 2).filter(|&x| x < n / 4)));
+            assert!(right.iter().copied().eq((0..n).map(|i| i / 2).filter(|&x| x >= n / 4)));
+        }
+    }
+
+    #[test]
+    fn test_eq_shape() {
+        // BTreeと同じく, 値が同じでも形が違えば等しくない.
+        let sequential = ArenaTree234::from_sorted_iter(0..20u64);
+        let mut inserted = ArenaTree234::new();
+        for i in 0..20 {
+            inserted.insert(i);
+        }
+        assert!(sequential.iter().eq(inserted.iter()));
+        assert_ne!(sequential, inserted);
+        // 節点がアリーナのどこにあっても, 形が同じなら等しい.
+        let mut moved = ArenaTree234::new();
+        moved.insert(100);
+        moved.delete(&100);
+        moved.append((0..20).collect());
+        assert_eq!(moved, sequential);
+        moved.clear();
+        assert_eq!(ArenaTree234::<u64>::new(), moved);
+    }
+}
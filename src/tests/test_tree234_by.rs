@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234_by::*;
+    use std::cmp::Ordering;
+    use std::ops::Bound;
+
+    #[test]
+    fn test_reverse() {
+        let mut tree = Tree234By::new(|a: &usize, b: &usize| b.cmp(a));
+        tree.append(vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90]);
+        let mut expected = vec![92, 40, 54, 53, 58, 24, 88, 59, 35, 30, 70, 42, 79, 96, 5, 49, 17, 43, 74, 82, 98, 13, 84, 16, 73, 63, 90];
+        expected.sort_by(|a, b| b.cmp(a));
+        assert!(tree.iter().eq(expected.iter()));
+        assert_eq!(tree.first(), Some(&98));
+        assert_eq!(tree.last(), Some(&5));
+        assert!(tree.find(&53));
+        assert!(!tree.find(&55));
+        assert_eq!(tree.lower_bound(Bound::Included(&50)), Some(&49));
+        assert_eq!(tree.upper_bound(Bound::Excluded(&49)), Some(&53));
+        assert_eq!(tree.range((Bound::Included(60), Bound::Excluded(40))).copied().collect::<Vec<_>>(), vec![59, 58, 54, 53, 49, 43, 42]);
+        assert_eq!(tree.count_range((Bound::Included(60), Bound::Excluded(40))), 7);
+        assert_eq!(tree.rank(&90), 3);
+        assert_eq!(tree.nth(3), Some(&90));
+        assert!(tree.delete(&90));
+        assert!(!tree.delete(&90));
+        assert_eq!(tree.len(), expected.len() - 1);
+        assert_eq!(tree.pop_first(), Some(98));
+        assert_eq!(tree.pop_last(), Some(5));
+    }
+
+    #[test]
+    fn test_by_key() {
+        let mut tree = Tree234By::by_key(|x: &(usize, &str)| x.0);
+        tree.insert((3, "c"));
+        tree.insert((1, "a"));
+        tree.insert((2, "b"));
+        assert!(tree.insert_unique((4, "d")));
+        // equal keys count as duplicates even if the rest of the value differs.
+        assert!(!tree.insert_unique((2, "x")));
+        assert!(tree.find(&(2, "anything")));
+        assert_eq!(tree.successor(&(2, "")), Some(&(3, "c")));
+        assert_eq!(tree.predecessor(&(2, "")), Some(&(1, "a")));
+        tree.insert((2, "y"));
+        assert_eq!(tree.count(&(2, "")), 2);
+        assert_eq!(tree.delete_all(&(2, "")), 2);
+        assert_eq!(
+            tree.iter().map(|x| x.1).collect::<Vec<_>>(),
+            vec!["a", "c", "d"]
+        );
+    }
+
+    #[test]
+    fn test_float() {
+        let mut tree = Tree234By::new(|a: &f64, b: &f64| a.total_cmp(b));
+        for x in [2.5, -1.0, 0.0, 3.25, -0.5, 2.5] {
+            tree.insert(x);
+        }
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.count(&2.5), 2);
+        assert_eq!(tree.range(0.0..=2.5).copied().collect::<Vec<_>>(), vec![0.0, 2.5, 2.5]);
+        assert_eq!(tree.search_and_get_range(&1.0), (Some(&0.0), Some(&2.5)));
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<_>>(),
+            vec![-1.0, -0.5, 0.0, 2.5, 2.5, 3.25]
+        );
+    }
+
+    #[test]
+    fn test_split_join() {
+        let mut tree = Tree234By::new(|a: &usize, b: &usize| b.cmp(a));
+        tree.append((0..100).collect());
+        let right = tree.split_off(&40);
+        assert!(tree.iter().copied().eq((41..100).rev()));
+        assert!(right.iter().copied().eq((0..=40).rev()));
+        let tree = Tree234By::join(tree, right);
+        assert!(tree.iter().copied().eq((0..100).rev()));
+        assert_eq!(tree.len(), 100);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut tree = Tree234By::new(|a: &usize, b: &usize| b.cmp(a));
+        tree.append((50..100).collect());
+        // 逆順では小さい値が後ろに来るので, まとめて構築した木をつなげる.
+        tree.append((0..50).collect());
+        assert_eq!(tree.validate(), Ok(()));
+        assert!(tree.iter().copied().eq((0..100).rev()));
+        // 間に入る値は一つずつ挿入する.
+        tree.append(vec![60, 10, 99]);
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(tree.len(), 103);
+        assert_eq!(tree.count(&60), 2);
+        assert_eq!(tree.first(), Some(&99));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut tree = Tree234By::new(|a: &usize, b: &usize| -> Ordering { a.cmp(b) });
+        tree.append((0..100).collect());
+        assert_eq!(tree.len(), 100);
+        tree.clear();
+        assert!(tree.is_empty());
+        assert_eq!(tree.first(), None);
+        assert_eq!(format!("{:?}", tree), "{}");
+    }
+}
@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::random;
+    use std::ops::Bound;
+
+    /// walk a Cursor over a tree at random and compare it with the index into the sorted values.
+    fn walk<const B: usize>(n: u64, steps: usize) {
+        let tree = BTree::<u64, B>::from_sorted_iter((0..n).map(|x| x / 2));
+        let model: Vec<u64> = tree.iter().copied().collect();
+        let mut cursor = tree.cursor_front();
+        // 幽霊の位置はmodel.len()で表す.
+        let mut index = 0;
+        // 鍵は0か1で, 進む向きを表す.
+        random::differential(n, steps, 2, |_, forward, _| {
+            if forward == 0 {
+                cursor.move_next();
+                index = if index == model.len() { 0 } else { index + 1 };
+            } else {
+                cursor.move_prev();
+                index = if index == 0 { model.len() } else { index - 1 };
+            }
+            assert_eq!(cursor.peek(), model.get(index));
+            assert_eq!(cursor.index(), (index < model.len()).then_some(index));
+            let prev = if index == 0 { None } else { model.get(index - 1) };
+            let next = if index == model.len() { model.first() } else { model.get(index + 1) };
+            assert_eq!(cursor.peek_prev(), prev);
+            assert_eq!(cursor.peek_next(), next);
+        });
+        // 端から端まで歩くと全ての値を順に通る.
+        let mut cursor = tree.cursor_back();
+        let mut values = vec![];
+        while let Some(&value) = cursor.peek() {
+            values.push(value);
+            cursor.move_prev();
+        }
+        values.reverse();
+        assert_eq!(values, model);
+    }
+
+    #[test]
+    fn test_walk() {
+        walk::<4>(0, 10);
+        walk::<4>(1, 10);
+        walk::<4>(200, 3000);
+        walk::<6>(1000, 3000);
+        walk::<16>(1000, 3000);
+    }
+
+    #[test]
+    fn test_lower_bound() {
+        let tree = Tree234::from((0..100).map(|x| x * 3).collect::<Vec<u64>>());
+        let model: Vec<u64> = tree.iter().copied().collect();
+        for x in 0..310 {
+            let index = model.partition_point(|&y| y < x);
+            let cursor = tree.cursor_lower_bound(Bound::Included(&x));
+            assert_eq!(cursor.peek(), model.get(index));
+            assert_eq!(cursor.index(), (index < model.len()).then_some(index));
+            let index = model.partition_point(|&y| y <= x);
+            let mut cursor = tree.cursor_lower_bound(Bound::Excluded(&x));
+            assert_eq!(cursor.peek(), model.get(index));
+            cursor.move_prev();
+            assert_eq!(cursor.peek(), if index == 0 { None } else { model.get(index - 1) });
+        }
+        assert_eq!(tree.cursor_lower_bound(Bound::Unbounded).peek(), Some(&0));
+    }
+
+    /// edit a tree through a CursorMut at random and compare it with a sorted Vec.
+    fn edit<const B: usize>(steps: usize) {
+        let mut tree = BTree::<u64, B>::new();
+        let mut model: Vec<u64> = vec![];
+        let mut index = 0;
+        random::differential(B as u64, steps, 6, |step, op, rng| {
+            let mut cursor = tree.cursor_front_mut();
+            for _ in 0..index {
+                cursor.move_next();
+            }
+            // 挿入する値は前後の値の間から選ぶ.
+            let lower = if index == 0 { 0 } else { model[index - 1] };
+            let upper = model.get(index).copied().unwrap_or(lower + 10);
+            match op {
+                0 => {
+                    cursor.move_next();
+                    index = if index == model.len() { 0 } else { index + 1 };
+                }
+                1 => {
+                    cursor.move_prev();
+                    index = if index == 0 { model.len() } else { index - 1 };
+                }
+                2 => {
+                    let removed = (index < model.len()).then(|| model.remove(index));
+                    assert_eq!(cursor.remove_current(), removed);
+                }
+                3 | 4 => {
+                    let value = lower + rng.below(upper - lower + 1);
+                    cursor.insert_before(value);
+                    model.insert(index, value);
+                    index += 1;
+                }
+                _ => {
+                    let (at, lower, upper) = if index == model.len() {
+                        (0, 0, model.first().copied().unwrap_or(10))
+                    } else {
+                        (index + 1, model[index], model.get(index + 1).copied().unwrap_or(model[index] + 10))
+                    };
+                    let value = lower + rng.below(upper - lower + 1);
+                    cursor.insert_after(value);
+                    model.insert(at, value);
+                    if at == 0 {
+                        index += 1;
+                    }
+                }
+            }
+            assert_eq!(cursor.peek(), model.get(index), "B = {B}, step {step}");
+            assert_eq!(cursor.index(), (index < model.len()).then_some(index));
+            assert_eq!(cursor.peek_prev(), if index == 0 { None } else { model.get(index - 1) });
+            let next = if index == model.len() { model.first() } else { model.get(index + 1) };
+            assert_eq!(cursor.peek_next(), next);
+            // 外した節点は手放すときに木へ戻す.
+            drop(cursor);
+            assert_eq!(tree.validate(), Ok(()), "B = {B}, step {step}");
+            assert!(tree.iter().eq(model.iter()), "B = {B}, step {step}");
+        });
+    }
+
+    /// walk a CursorMut over a tree like walk, and then edit it, so that the nodes are taken out and put back.
+    fn walk_mut<const B: usize>(n: u64, steps: usize) {
+        let mut tree = BTree::<u64, B>::from_sorted_iter((0..n).map(|x| x / 2));
+        let model: Vec<u64> = tree.iter().copied().collect();
+        let mut cursor = tree.cursor_back_mut();
+        let mut index = model.len().saturating_sub(1);
+        random::differential(n, steps, 2, |_, forward, _| {
+            if forward == 0 {
+                cursor.move_next();
+                index = if index == model.len() { 0 } else { index + 1 };
+            } else {
+                cursor.move_prev();
+                index = if index == 0 { model.len() } else { index - 1 };
+            }
+            assert_eq!(cursor.peek(), model.get(index));
+            assert_eq!(cursor.index(), (index < model.len()).then_some(index));
+            let prev = if index == 0 { None } else { model.get(index - 1) };
+            let next = if index == model.len() { model.first() } else { model.get(index + 1) };
+            assert_eq!(cursor.peek_prev(), prev);
+            assert_eq!(cursor.peek_next(), next);
+        });
+        drop(cursor);
+        assert_eq!(tree.validate(), Ok(()));
+        assert!(tree.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn test_walk_mut() {
+        walk_mut::<4>(0, 10);
+        walk_mut::<4>(1, 10);
+        walk_mut::<4>(200, 3000);
+        walk_mut::<6>(1000, 3000);
+        walk_mut::<16>(1000, 3000);
+    }
+
+    fn count_nodes<const B: usize>(tree: &BTree<u64, B>) -> usize {
+        1 + tree.children.iter().map(|child| count_nodes(child)).sum::<usize>()
+    }
+
+    #[test]
+    fn test_amortized_moves() {
+        // 端から端まで歩くと, 各節点に入って出るのは1回ずつなので, 節点を外す回数と戻す回数はどちらも節点の数より少ない.
+        for n in [1, 10, 1000, 100000] {
+            let mut tree = Tree234::from_sorted_iter(0..n);
+            let nodes = count_nodes(&tree);
+            let mut cursor = tree.cursor_front_mut();
+            let mut depth = cursor.path.len();
+            let mut visits = depth;
+            for forward in [true, false] {
+                for _ in 0..n {
+                    if forward {
+                        cursor.move_next();
+                    } else {
+                        cursor.move_prev();
+                    }
+                    visits += cursor.path.len().abs_diff(depth);
+                    depth = cursor.path.len();
+                }
+            }
+            assert_eq!(cursor.peek(), Some(&0));
+            assert!(visits <= 4 * nodes, "n = {n}: {visits} visits for {nodes} nodes");
+        }
+    }
+
+    #[test]
+    fn test_edit() {
+        edit::<4>(3000);
+        edit::<6>(3000);
+        edit::<8>(3000);
+    }
+
+    #[test]
+    #[should_panic(expected = "between its neighbours")]
+    fn test_insert_unsorted() {
+        let mut tree = Tree234::from(vec![1, 3, 5]);
+        let mut cursor = tree.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(4);
+    }
+}
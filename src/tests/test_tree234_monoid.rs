@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::super::tree234_monoid::*;
+    use super::super::super::tree234_validate::*;
+    use super::super::random::{self, Model};
+
+    const P: u64 = 1_000_000_007;
+
+    /// composition of affine maps x -> a x + b, which is not commutative.
+    struct Affine;
+
+    impl Monoid<u64> for Affine {
+        type Value = (u64, u64);
+        fn identity() -> (u64, u64) {
+            (1, 0)
+        }
+        fn single(value: &u64) -> (u64, u64) {
+            (value % 7 + 2, *value)
+        }
+        fn op(a: &(u64, u64), b: &(u64, u64)) -> (u64, u64) {
+            (a.0 * b.0 % P, (b.0 * a.1 + b.1) % P)
+        }
+    }
+
+    fn naive<M: Monoid<u64>>(values: &[u64]) -> M::Value {
+        values.iter().fold(M::identity(), |acc, x| M::op(&acc, &M::single(x)))
+    }
+
+    /// the folds of a BTree over a non-commutative and a summing monoid against the model.
+    fn differential<const B: usize>(steps: usize, keys: u64) {
+        let mut tree = BTree::<u64, B, Affine>::new();
+        let mut sum = BTree::<u64, B, Sum>::new();
+        let mut model = Model::default();
+        random::differential(B as u64, steps, keys, |step, key, rng| {
+            match rng.below(6) {
+                0..=2 => {
+                    tree.insert(key);
+                    sum.insert(key);
+                    model.insert(key);
+                }
+                3 | 4 => {
+                    let found = model.delete(key);
+                    assert_eq!(tree.delete(&key), found);
+                    assert_eq!(sum.delete(&key), found);
+                }
+                _ => {
+                    let right = tree.split_off(&key);
+                    let pos = model.lower(key);
+                    assert_eq!(tree.fold(..), naive::<Affine>(&model[..pos]));
+                    assert_eq!(right.fold(..), naive::<Affine>(&model[pos..]));
+                    tree.append_tree(right);
+                    let right = sum.split_off(&key);
+                    sum = BTree::join(std::mem::take(&mut sum), right);
+                }
+            }
+            assert_eq!(tree.validate_aggregate(), Ok(()), "B = {B}, step {step}");
+            assert_eq!(sum.validate_aggregate(), Ok(()), "B = {B}, step {step}");
+            assert_eq!(tree.fold(..), naive::<Affine>(&model), "B = {B}, step {step}");
+            let (l, r) = rng.pair(keys);
+            let (start, end) = (model.lower(l), model.upper(r));
+            assert_eq!(tree.fold(l..=r), naive::<Affine>(&model[start..end]), "B = {B}, step {step}");
+            assert_eq!(sum.fold(l..r), model.iter().filter(|&&x| l <= x && x < r).sum::<u64>());
+
+            // 和が上限以下である最長の区間を二分探索で求める.
+            let limit = rng.below(keys * 8);
+            let start = rng.below(model.len() as u64 + 1) as usize;
+            let mut end = start;
+            while end < model.len() && model[start..=end].iter().sum::<u64>() <= limit {
+                end += 1;
+            }
+            assert_eq!(sum.max_right(start, |&s| s <= limit), end, "B = {B}, step {step}");
+            let mut begin = start;
+            while begin > 0 && model[begin - 1..start].iter().sum::<u64>() <= limit {
+                begin -= 1;
+            }
+            assert_eq!(sum.min_left(start, |&s| s <= limit), begin, "B = {B}, step {step}");
+        });
+    }
+
+    #[test]
+    fn test_differential() {
+        differential::<4>(2000, 64);
+        differential::<6>(2000, 1000);
+        differential::<16>(2000, 1000);
+    }
+
+    #[test]
+    fn test_max_min() {
+        let tree = BTree::<i32, 4, Max>::from(vec![5, 1, 9, 3, 7]);
+        assert_eq!(tree.fold(..), Some(9));
+        assert_eq!(tree.fold(..9), Some(7));
+        assert_eq!(tree.fold(10..), None);
+        // 最大値が6以下である最長の接頭辞.
+        assert_eq!(tree.max_right(0, |m| m.is_none_or(|m| m <= 6)), 3);
+        let tree = BTree::<i32, 4, Min>::from(vec![5, 1, 9, 3, 7]);
+        assert_eq!(tree.fold(2..), Some(3));
+        assert_eq!(tree.min_left(5, |m| m.is_none_or(|m| m >= 5)), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_max_right_out_of_range() {
+        let tree = BTree::<i32, 4, Sum>::from(vec![1, 2, 3]);
+        tree.max_right(4, |_| true);
+    }
+
+    #[test]
+    fn test_aggregate_mismatch() {
+        let mut tree = BTree::<u64, 4, Sum>::from((0..20).collect::<Vec<_>>());
+        assert_eq!(tree.validate_aggregate(), Ok(()));
+        // 長さは正しいままで, 子の集約値だけを壊す.
+        tree.child_mut(1).aggregate += 1;
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(tree.validate_aggregate(), Err(InvariantError::AggregateMismatch { depth: 1 }));
+        tree.child_mut(1).update();
+        assert_eq!(tree.validate_aggregate(), Ok(()));
+        tree.aggregate = 0;
+        assert_eq!(tree.validate_aggregate(), Err(InvariantError::AggregateMismatch { depth: 0 }));
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::super::tree234_persistent::*;
+    use super::super::random;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    /// addresses of every node of the tree.
+    fn nodes<const B: usize>(tree: &PersistentBTree<u64, B>) -> HashSet<*const PersistentNode<u64, B>> {
+        let mut nodes = HashSet::new();
+        let mut stack = vec![&tree.root];
+        while let Some(node) = stack.pop() {
+            nodes.insert(Rc::as_ptr(node));
+            stack.extend(node.children.iter());
+        }
+        nodes
+    }
+
+    /// apply random operations to a PersistentBTree and a BTree, keeping every version,
+    /// and check at the end that no version was changed by the later operations.
+    fn versions<const B: usize>(steps: usize, keys: u64) {
+        let mut tree = PersistentBTree::<u64, B>::new();
+        let mut boxed = BTree::<u64, B>::new();
+        let mut history = vec![(tree.clone(), boxed.iter().copied().collect::<Vec<_>>())];
+        random::differential(11, steps, keys, |step, key, rng| {
+            // 過去の版から分岐させることもある.
+            if rng.below(16) == 0 {
+                let version = rng.below(history.len() as u64) as usize;
+                tree = history[version].0.clone();
+                boxed = BTree::from_sorted_iter(history[version].1.iter().copied());
+            }
+            match rng.below(8) {
+                0..=3 => {
+                    tree.insert(key);
+                    boxed.insert(key);
+                }
+                4 | 5 => assert_eq!(tree.delete(&key), boxed.delete(&key)),
+                6 => assert_eq!(tree.pop_first(), boxed.pop_first()),
+                _ => assert_eq!(tree.pop_last(), boxed.pop_last()),
+            }
+            assert_eq!(tree.validate(), Ok(()), "B = {B}, step {step}");
+            assert_eq!(tree.len(), boxed.len());
+            assert!(tree.iter().eq(boxed.iter()), "B = {B}, step {step}");
+            assert_eq!(tree.find(&key), boxed.find(&key));
+            assert_eq!(tree.rank(&key), boxed.rank(&key));
+            assert_eq!(tree.nth(key as usize), boxed.nth(key as usize));
+            history.push((tree.clone(), boxed.iter().copied().collect()));
+        });
+        for (version, values) in &history {
+            assert_eq!(version.validate(), Ok(()));
+            assert!(version.iter().eq(values.iter()));
+        }
+    }
+
+    #[test]
+    fn test_versions() {
+        versions::<4>(3000, 64);
+        versions::<4>(3000, 10000);
+        versions::<8>(2000, 256);
+    }
+
+    #[test]
+    fn test_sharing() {
+        let tree = PersistentTree234::from((0..1000).map(|x| x * 2).collect::<Vec<u64>>());
+        let copy = tree.clone();
+        assert!(copy.ptr_eq(&tree));
+        let old = nodes(&tree);
+
+        // 挿入・削除で新しく作られるノードは経路上の数個だけ.
+        let inserted = tree.with_inserted(501);
+        let new = nodes(&inserted).difference(&old).count();
+        assert!(new <= 2 * inserted.height() + 2, "{new} nodes were copied");
+        let deleted = tree.with_deleted(&500);
+        let new = nodes(&deleted).difference(&old).count();
+        assert!(new <= 3 * deleted.height() + 2, "{new} nodes were copied");
+
+        assert!(!tree.find(&501) && inserted.find(&501));
+        assert!(tree.find(&500) && !deleted.find(&500));
+        assert_eq!(nodes(&tree), old);
+        assert!(copy.ptr_eq(&tree));
+
+        // 存在しない値の削除では何も複製しない.
+        let mut same = tree.clone();
+        assert!(!same.delete(&501));
+        assert!(same.ptr_eq(&tree));
+    }
+
+    #[test]
+    fn test_from_bulk() {
+        // BTree::fromと同じく, 一つずつ挿入せずに一度に組み立てる.
+        for n in [0, 1, 3, 4, 100, 1000] {
+            let values: Vec<u64> = (0..n).rev().chain(0..n / 2).collect();
+            let tree = PersistentTree234::from(values.clone());
+            let boxed = Tree234::from(values);
+            assert_eq!(tree.validate(), Ok(()));
+            assert!(tree.iter().eq(boxed.iter()));
+            assert_eq!(tree.height(), boxed.height());
+        }
+    }
+
+    #[test]
+    fn test_without_clone() {
+        // 共有されていないノードは複製せずに書き換える.
+        let mut tree = PersistentTree234::new();
+        for i in 0..100u64 {
+            tree.insert(i);
+        }
+        let before = nodes(&tree);
+        tree.insert(100);
+        tree.delete(&0);
+        assert!(nodes(&tree).difference(&before).count() <= 2);
+        assert_eq!(tree.first(), Some(&1));
+        assert_eq!(tree.last(), Some(&100));
+    }
+}
@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::random::XorShift;
+    use std::collections::BTreeSet;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Op {
+        Insert(u64),
+        Delete(u64),
+        Find(u64),
+        Range(u64, u64),
+        PopFirst,
+        PopLast,
+    }
+
+    fn random_ops(rng: &mut XorShift, len: usize, keys: u64) -> Vec<Op> {
+        (0..len)
+            .map(|_| match rng.below(10) {
+                0..=3 => Op::Insert(rng.below(keys)),
+                4..=6 => Op::Delete(rng.below(keys)),
+                7 => Op::Find(rng.below(keys)),
+                8 => {
+                    let (l, r) = rng.pair(keys);
+                    Op::Range(l, r)
+                }
+                _ if rng.below(2) == 0 => Op::PopFirst,
+                _ => Op::PopLast,
+            })
+            .collect()
+    }
+
+    /// apply ops to a Tree234 and a BTreeSet, comparing them after each step.
+    fn run(ops: &[Op]) -> Result<(), String> {
+        let mut tree = Tree234::new();
+        let mut set = BTreeSet::new();
+        for (step, &op) in ops.iter().enumerate() {
+            let same = match op {
+                Op::Insert(x) => tree.insert_unique(x) == set.insert(x),
+                Op::Delete(x) => tree.delete(&x) == set.remove(&x),
+                Op::Find(x) => tree.find(&x) == set.contains(&x),
+                Op::Range(l, r) => tree.range(l..r).eq(set.range(l..r)) && tree.count_range(l..r) == set.range(l..r).count(),
+                Op::PopFirst => tree.pop_first() == set.pop_first(),
+                Op::PopLast => tree.pop_last() == set.pop_last(),
+            };
+            if !same {
+                return Err(format!("step {step}: {op:?} returned a different result"));
+            }
+            if !tree.iter().eq(set.iter()) || tree.len() != set.len() {
+                return Err(format!("step {step}: {op:?} left different contents\n{tree:#}"));
+            }
+            if let Err(error) = tree.validate() {
+                return Err(format!("step {step}: {op:?} broke the tree: {error}\n{tree:#}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// like run, but a panic inside the tree is a failure too.
+    fn fails(ops: &[Op]) -> bool {
+        !matches!(catch_unwind(AssertUnwindSafe(|| run(ops))), Ok(Ok(())))
+    }
+
+    /// shrink a failing sequence while it keeps failing:
+    /// drop chunks of decreasing size, then make the keys smaller.
+    fn shrink(mut ops: Vec<Op>, fails: impl Fn(&[Op]) -> bool) -> Vec<Op> {
+        let mut chunk = ops.len() / 2;
+        while chunk > 0 {
+            let mut start = 0;
+            while start < ops.len() {
+                let mut candidate = ops.clone();
+                candidate.drain(start..(start + chunk).min(ops.len()));
+                if fails(&candidate) {
+                    ops = candidate;
+                } else {
+                    start += chunk;
+                }
+            }
+            chunk /= 2;
+        }
+        for i in 0..ops.len() {
+            loop {
+                let smaller = match ops[i] {
+                    Op::Insert(x) if x > 0 => Op::Insert(x - 1),
+                    Op::Delete(x) if x > 0 => Op::Delete(x - 1),
+                    Op::Find(x) if x > 0 => Op::Find(x - 1),
+                    Op::Range(l, r) if l > 0 => Op::Range(l - 1, r),
+                    Op::Range(l, r) if r > l => Op::Range(l, r - 1),
+                    _ => break,
+                };
+                let mut candidate = ops.clone();
+                candidate[i] = smaller;
+                if !fails(&candidate) {
+                    break;
+                }
+                ops = candidate;
+            }
+        }
+        ops
+    }
+
+    /// print ops as Rust code which replays them.
+    fn to_rust(ops: &[Op]) -> String {
+        let mut code = String::from("let mut tree = Tree234::new();\n");
+        for op in ops {
+            code += &match op {
+                Op::Insert(x) => format!("tree.insert_unique({x});\n"),
+                Op::Delete(x) => format!("tree.delete(&{x});\n"),
+                Op::Find(x) => format!("tree.find(&{x});\n"),
+                Op::Range(l, r) => format!("tree.range({l}..{r}).count();\n"),
+                Op::PopFirst => "tree.pop_first();\n".to_string(),
+                Op::PopLast => "tree.pop_last();\n".to_string(),
+            };
+        }
+        code += "tree.validate().unwrap();\n";
+        code
+    }
+
+    fn differential(seeds: u64, len: usize, keys: u64) {
+        for seed in 0..seeds {
+            let ops = random_ops(&mut XorShift::new(seed), len, keys);
+            if let Err(error) = catch_unwind(AssertUnwindSafe(|| run(&ops))).unwrap_or(Err("panicked".to_string())) {
+                let minimal = shrink(ops, fails);
+                panic!(
+                    "seed {seed}: {error}\nminimal reproducer ({} steps):\n{}",
+                    minimal.len(),
+                    to_rust(&minimal)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_differential_small_keys() {
+        // 値の種類が少ないと, 削除で木が何度も縮む.
+        differential(50, 1000, 32);
+    }
+
+    #[test]
+    fn test_differential_large_keys() {
+        differential(10, 3000, 1 << 20);
+    }
+
+    #[test]
+    fn test_shrink() {
+        // a fake failure: the sequence fails once 3 and 7 are both in the tree.
+        let fails = |ops: &[Op]| {
+            let mut tree = Tree234::new();
+            ops.iter().any(|&op| {
+                match op {
+                    Op::Insert(x) => {
+                        tree.insert_unique(x);
+                    }
+                    Op::Delete(x) => {
+                        tree.delete(&x);
+                    }
+                    _ => {}
+                }
+                tree.find(&3) && tree.find(&7)
+            })
+        };
+        let ops = random_ops(&mut XorShift::new(1), 500, 16);
+        assert!(fails(&ops));
+        let minimal = shrink(ops, fails);
+        assert_eq!(minimal.len(), 2);
+        assert!(minimal.contains(&Op::Insert(3)));
+        assert!(minimal.contains(&Op::Insert(7)));
+        let code = to_rust(&[Op::Insert(3), Op::Delete(7), Op::Range(1, 4), Op::PopFirst]);
+        assert_eq!(
+            code,
+            "let mut tree = Tree234::new();\ntree.insert_unique(3);\ntree.delete(&7);\ntree.range(1..4).count();\ntree.pop_first();\ntree.validate().unwrap();\n"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::super::tree234_monoid::*;
+    use super::super::super::tree234_shape::*;
+    use super::super::super::tree234_validate::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut tree = Tree234::new();
+        assert_eq!(tree.to_shape(), "[]");
+        assert_eq!("[]".parse::<Tree234<i32>>(), Ok(tree.clone()));
+        for i in 0..50 {
+            tree.insert(i * 7 % 50);
+            let shape = tree.to_shape();
+            let parsed: Tree234<i32> = shape.parse().unwrap();
+            assert_eq!(parsed.to_shape(), shape);
+            assert_eq!(parsed.len(), tree.len());
+            assert!(parsed.iter().eq(tree.iter()));
+        }
+    }
+
+    #[test]
+    fn test_exact_shape() {
+        let tree: Tree234<i32> = " [ [1] 2 [3 4] ] ".parse().unwrap();
+        assert_eq!(tree.to_shape(), "[[1] 2 [3 4]]");
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.height(), 2);
+        let mut tree = tree;
+        tree.insert(5);
+        assert_eq!(tree.to_shape(), "[[1] 2 [3 4 5]]");
+        tree.insert(6);
+        assert_eq!(tree.to_shape(), "[[1] 2 [3] 4 [5 6]]");
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let tree: BTree<u64, 4, Sum> = "[[1 2] 3 [4] 5 [6]]".parse().unwrap();
+        assert_eq!(tree.fold(..), 21);
+        assert_eq!(tree.fold(3..6), 12);
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        assert_eq!("".parse::<Tree234<i32>>(), Err(ShapeError::UnexpectedEnd));
+        assert_eq!("[[1] 2".parse::<Tree234<i32>>(), Err(ShapeError::UnexpectedEnd));
+        assert_eq!(
+            "[[1] 2]".parse::<Tree234<i32>>(),
+            Err(ShapeError::UnexpectedChar { pos: 6, found: ']' })
+        );
+        assert_eq!(
+            "[1 [2]]".parse::<Tree234<i32>>(),
+            Err(ShapeError::UnexpectedChar { pos: 3, found: '[' })
+        );
+        assert_eq!(
+            "[[1] 2 [3] [4]]".parse::<Tree234<i32>>(),
+            Err(ShapeError::UnexpectedChar { pos: 11, found: '[' })
+        );
+        assert_eq!("[1] 2".parse::<Tree234<i32>>(), Err(ShapeError::UnexpectedChar { pos: 4, found: '2' }));
+        let error = "[1 x]".parse::<Tree234<i32>>().unwrap_err();
+        assert_eq!(error, ShapeError::InvalidValue { pos: 3, token: "x".to_string() });
+        assert_eq!(error.to_string(), "\"x\" at 3 is not a valid value");
+    }
+
+    #[test]
+    fn test_invalid_tree() {
+        assert_eq!(
+            "[[2] 1 [3]]".parse::<Tree234<i32>>(),
+            Err(ShapeError::Invariant(InvariantError::OutOfRange { depth: 1, index: 0 }))
+        );
+        assert_eq!(
+            "[[1] 2 [[3] 4 [5]]]".parse::<Tree234<i32>>(),
+            Err(ShapeError::Invariant(InvariantError::UnevenDepth { expected: 1, found: 2 }))
+        );
+        assert_eq!(
+            "[[1] 2 []]".parse::<Tree234<i32>>(),
+            Err(ShapeError::Invariant(InvariantError::NodeSize { depth: 1, size: 0 }))
+        );
+        assert_eq!(
+            "[[1 2 3 4] 5 [6]]".parse::<Tree234<i32>>(),
+            Err(ShapeError::Invariant(InvariantError::NodeSize { depth: 1, size: 4 }))
+        );
+        // with B = 6 a node holds up to 5 values.
+        assert!("[[1 2 3 4] 5 [6]]".parse::<BTree<i32, 6>>().is_ok());
+    }
+
+    #[test]
+    fn test_too_deep() {
+        // too deep an input is rejected without overflowing the stack.
+        let input = "[".repeat(1 << 20);
+        assert_eq!(input.parse::<Tree234<i32>>(), Err(ShapeError::TooDeep { pos: 64 }));
+        let error = format!("{}1{}", "[".repeat(65), "]".repeat(65)).parse::<Tree234<i32>>().unwrap_err();
+        assert_eq!(error, ShapeError::TooDeep { pos: 64 });
+        assert_eq!(error.to_string(), "the node at 64 is nested deeper than 64 levels");
+        // up to 64 levels still parse.
+        assert!(matches!(
+            format!("{}1{}", "[".repeat(64), "]".repeat(64)).parse::<Tree234<i32>>(),
+            Err(ShapeError::Invariant(_))
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use super::super::super::tree234::*;
+    use super::super::super::tree234_monoid::*;
+    use super::super::super::tree234_trace::*;
+    use super::super::random;
+
+    /// a hook which only keeps the events.
+    #[derive(Default)]
+    struct Events(Vec<TraceEvent>);
+
+    impl<T, const B: usize, M: Monoid<T>> TraceHook<T, B, M> for Events {
+        fn step(&mut self, event: &TraceEvent, _: &BTree<T, B, M>) {
+            self.0.push(event.clone());
+        }
+    }
+
+    /// a traced BTree against an untraced one, which serves as the model.
+    fn differential<const B: usize>(steps: usize, keys: u64) {
+        let mut plain = BTree::<u64, B, Sum>::new();
+        let mut traced = BTree::<u64, B, Sum>::new();
+        let mut events = Events::default();
+        random::differential(B as u64, steps, keys, |step, key, rng| {
+            if rng.below(2) == 0 {
+                plain.insert(key);
+                traced.insert_traced(key, &mut events);
+            } else {
+                assert_eq!(traced.delete_traced(&key, &mut events), plain.delete(&key));
+            }
+            assert_eq!(traced.validate(), Ok(()), "B = {B}, step {step}");
+            assert_eq!(traced, plain, "B = {B}, step {step}");
+            assert_eq!(traced.len(), plain.len());
+            assert_eq!(traced.fold(..), plain.fold(..));
+        });
+        for name in ["Split", "Rotate", "Merge", "Shrink", "SwapWithPredecessor", "Insert", "Remove"] {
+            assert!(events.0.iter().any(|event| format!("{event:?}").starts_with(name)), "B = {B}, {name}");
+        }
+    }
+
+    #[test]
+    fn test_differential() {
+        differential::<4>(3000, 64);
+        differential::<6>(3000, 200);
+    }
+
+    #[test]
+    fn test_events() {
+        let mut tree: Tree234<i32> = "[[1] 2 [3] 4 [5 6 7]]".parse().unwrap();
+        let mut recorder = TraceRecorder::new();
+        for value in [2, 4, 1, 7] {
+            tree.delete_traced(&value, &mut recorder);
+        }
+        let steps: Vec<_> = recorder.steps.iter().map(|step| (step.operation.as_str(), step.event.clone(), step.snapshot.to_shape())).collect();
+        let step = |operation, event, shape: &str| (operation, event, shape.to_string());
+        assert_eq!(
+            steps,
+            vec![
+                step("delete 2", None, "[[1] 2 [3] 4 [5 6 7]]"),
+                step("delete 2", Some(TraceEvent::Merge { path: vec![], pos: 0 }), "[[1 2 3] 4 [5 6 7]]"),
+                step("delete 2", Some(TraceEvent::Remove { path: vec![0], pos: 1 }), "[[1 3] 4 [5 6 7]]"),
+                step("delete 4", None, "[[1 3] 4 [5 6 7]]"),
+                step("delete 4", Some(TraceEvent::Remove { path: vec![0], pos: 1 }), "[[1] 4 [5 6 7]]"),
+                step("delete 4", Some(TraceEvent::SwapWithPredecessor { path: vec![], pos: 0 }), "[[1] 3 [5 6 7]]"),
+                step("delete 1", None, "[[1] 3 [5 6 7]]"),
+                step(
+                    "delete 1",
+                    Some(TraceEvent::Rotate { path: vec![], pos: 0, direction: RotateDirection::Left }),
+                    "[[1 3] 5 [6 7]]",
+                ),
+                step("delete 1", Some(TraceEvent::Remove { path: vec![0], pos: 0 }), "[[3] 5 [6 7]]"),
+                step("delete 7", None, "[[3] 5 [6 7]]"),
+                step("delete 7", Some(TraceEvent::Remove { path: vec![1], pos: 1 }), "[[3] 5 [6]]"),
+            ]
+        );
+        // the last copy has the same shape as the actual tree.
+        assert_eq!(recorder.steps.last().unwrap().snapshot, tree);
+
+        let mut tree: Tree234<i32> = "[[3] 5 [6]]".parse().unwrap();
+        let mut recorder = TraceRecorder::new();
+        tree.delete_traced(&6, &mut recorder);
+        let events: Vec<_> = recorder.steps.iter().filter_map(|step| step.event.clone()).collect();
+        assert_eq!(events, vec![TraceEvent::Shrink { path: vec![] }, TraceEvent::Remove { path: vec![], pos: 2 }]);
+        assert_eq!(tree.to_shape(), "[3 5]");
+
+        let mut tree: Tree234<i32> = "[[1 2] 3 [4]]".parse().unwrap();
+        let mut recorder = TraceRecorder::new();
+        tree.delete_traced(&4, &mut recorder);
+        let events: Vec<_> = recorder.steps.iter().filter_map(|step| step.event.clone()).collect();
+        assert_eq!(
+            events,
+            vec![
+                TraceEvent::Rotate { path: vec![], pos: 1, direction: RotateDirection::Right },
+                TraceEvent::Remove { path: vec![1], pos: 1 },
+            ]
+        );
+        assert_eq!(tree.to_shape(), "[[1] 2 [3]]");
+
+        let mut tree = Tree234::new();
+        let mut recorder = TraceRecorder::new();
+        for i in 1..=4 {
+            tree.insert_traced(i, &mut recorder);
+        }
+        let events: Vec<_> = recorder.steps.iter().filter_map(|step| step.event.clone()).collect();
+        assert_eq!(
+            events,
+            vec![
+                TraceEvent::Insert { path: vec![], pos: 0 },
+                TraceEvent::Insert { path: vec![], pos: 1 },
+                TraceEvent::Insert { path: vec![], pos: 2 },
+                TraceEvent::Split { path: vec![] },
+                TraceEvent::Insert { path: vec![1], pos: 1 },
+            ]
+        );
+        assert_eq!(events[3].path(), &[] as &[usize]);
+        // right after the split the root is [2], with the children [1] and [3].
+        assert_eq!(recorder.steps[7].snapshot.height(), 2);
+        assert_eq!(recorder.steps[7].snapshot.len(), 3);
+    }
+
+    #[test]
+    fn test_html() {
+        let mut tree = Tree234::new();
+        let mut recorder = TraceRecorder::new();
+        for i in [3, 1, 4, 1, 5] {
+            tree.insert_traced(i, &mut recorder);
+        }
+        tree.delete_traced(&3, &mut recorder);
+        let html = recorder.to_html("<demo>");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>&lt;demo&gt;</title>"));
+        assert_eq!(html.matches("<div class=\"step").count(), recorder.steps.len());
+        assert_eq!(html.matches("<svg").count(), recorder.steps.len());
+        assert!(html.contains("class=\"changed\""));
+        assert!(html.contains("delete 3"));
+    }
+}
@@ -1,7 +1,88 @@
-use std::{fmt::Debug, ptr};
+use crate::{
+    array_vec::ArrayVec,
+    tree234_trace::{RotateDirection, TraceEvent, Tracer},
+    Compare, Link, LinkMut, Monoid, Owned,
+};
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+    ptr,
+};
 
 ///
-/// A tree struct which implements 2-3-4 tree.
+/// A B-tree whose nodes have at most B children and B - 1 values.
+/// B must be an even number of at least 4.
+///
+/// Insertion splits full nodes and deletion rotates or merges sparse nodes on the way down,
+/// so every operation walks the tree only once from the root.
+/// A larger B makes the tree lower and its nodes wider, which is friendlier to the cache.
+/// Nodes built by `split_off`, `join` and the bulk constructors may hold fewer than B / 2 - 1 values,
+/// but every node has at least 1 value and all leaves are at the same depth,
+/// so the height stays O(log n).
+/// Like [`Tree234`], the tree is a multiset.
+///
+/// # Example
+/// ```
+/// use algorithm::BTree;
+/// let mut tree = BTree::<i32, 16>::new();
+/// for i in 0..100 {
+///     tree.insert(i);
+/// }
+/// assert!(tree.find(&50));
+/// assert_eq!(tree.height(), 2);
+/// ```
+///
+/// M is a [`Monoid`] whose product over each subtree is cached in the node, see [`BTree::fold`].
+/// The default `()` caches nothing.
+///
+/// L is the [`Link`] through which a node holds its children. The default [`Owned`] boxes them,
+/// and [`Shared`](crate::Shared) lets [`PersistentBTree`](crate::PersistentBTree) run the same algorithms on shared nodes.
+///
+pub struct BTree<T, const B: usize = 4, M: Monoid<T> = (), L: Link = Owned> {
+    /// 
+    /// values of the node in ascending order.
+    /// 0 <= data.len() <= B - 1
+    /// 
+    pub(crate) data: ArrayVec<T, B>,
+    /// 
+    /// children of the node. a leaf has none, and an internal node has data.len() + 1.
+    /// 
+    pub(crate) children: ArrayVec<L::Ptr<BTree<T, B, M, L>>, B>,
+    /// 
+    /// number of values in the subtree rooted at this node.
+    /// 
+    pub(crate) length: usize,
+    /// 
+    /// product of M over the values of the subtree in ascending order.
+    /// 
+    pub(crate) aggregate: M::Value,
+}
+
+impl<T: Debug, const B: usize, M: Monoid<T>, L: Link> Debug for BTree<T, B, M, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let children: Vec<&Self> = self.children.iter().map(|child| &**child).collect();
+        f.debug_struct("BTree")
+            .field("data", &self.data)
+            .field("children", &children)
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl<T: Clone, const B: usize, M: Monoid<T>, L: Link> Clone for BTree<T, B, M, L> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            children: self.children.iter().map(L::clone_ptr).collect(),
+            length: self.length,
+            aggregate: self.aggregate.clone(),
+        }
+    }
+}
+
+///
+/// A tree struct which implements 2-3-4 tree, the B-tree whose nodes have 2, 3 or 4 children.
 ///
 /// # The benefits of 2-3-4 tree
 /// - The height of the tree is always O(log n).
@@ -9,6 +90,11 @@ use std::{fmt::Debug, ptr};
 /// - The tree is always sorted.
 /// - Search, insert, and delete operations are O(log n).
 ///
+/// # Duplicates
+/// The tree is a multiset: `insert` keeps equal values side by side,
+/// `delete` (or `delete_one`) removes a single occurrence and `delete_all` removes every one.
+/// Use `insert_unique` instead of `insert` to use the tree as a set.
+///
 /// # Example
 /// ```
 /// use algorithm::Tree234;
@@ -16,79 +102,58 @@ use std::{fmt::Debug, ptr};
 /// tree.insert(1);
 /// tree.insert(2);
 /// tree.insert(3);
-/// assert(tree.find(&1));
-/// assert(tree.find(&2));
-/// assert(!tree.find(&4));
+/// assert!(tree.find(&1));
+/// assert!(tree.find(&2));
+/// assert!(!tree.find(&4));
 /// tree.delete(&2);
-/// assert(!tree.find(&2));
+/// assert!(!tree.find(&2));
 /// ```
 ///
 ///
-#[derive(Debug, Clone)]
-pub struct Tree234<T> {
-    /// 
-    /// array which contains node's data.
-    /// 
-    pub(crate) data: [Option<T>; 4],
-    /// 
-    /// array which contains node's children.
-    /// 
-    pub(crate) children: [Option<Box<Tree234<T>>>; 4],
-    /// 
-    /// size of node.
-    /// 0 <= size <= 3
-    /// 
-    pub(crate) size: usize,
-}
+pub type Tree234<T> = BTree<T, 4>;
 
-impl<T: PartialEq> PartialEq for Tree234<T> {
+impl<T: PartialEq, const B: usize, M: Monoid<T>, L: Link> PartialEq for BTree<T, B, M, L> {
     fn eq(&self, other: &Self) -> bool {
-        if self.size != other.size {
-            return false;
-        }
-        for i in 0..self.size {
-            if self.data[i].as_ref().unwrap() != other.data[i].as_ref().unwrap() {
-                return false;
-            }
-        }
-        for i in 0..=self.size {
-            if self.children[i].as_ref().unwrap() != other.children[i].as_ref().unwrap() {
-                return false;
-            }
-        }
-        true
-    }
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
+        self.data == other.data && self.children.iter().map(|child| &**child).eq(other.children.iter().map(|child| &**child))
     }
 }
 
-impl<T: Eq> Eq for Tree234<T> {}
+impl<T: Eq, const B: usize, M: Monoid<T>, L: Link> Eq for BTree<T, B, M, L> {}
 
-impl<T: Ord + Debug> From<Vec<T>> for Tree234<T> {
+impl<T: Ord, const B: usize, M: Monoid<T>, L: Link> From<Vec<T>> for BTree<T, B, M, L> {
     ///
-    /// a method to make a Tree234 from a Vec.
+    /// a method to make a Tree234 from a Vec in O(n log n).
+    /// duplicated values are stored only once; use `from_sorted_iter` to keep them.
     /// ```
     /// use algorithm::Tree234;
-    /// let tree = Tree234::from(vec![1, 2, 3]);
-    /// assert(tree.find(&1));
+    /// let tree = Tree234::from(vec![1, 2, 3, 1]);
+    /// assert!(tree.find(&1));
+    /// assert_eq!(tree.len(), 3);
     /// ```
     ///
-    fn from(v: Vec<T>) -> Self {
-        let mut tree = Tree234::new();
-        for i in v {
-            tree.insert(i);
-        }
+    fn from(mut v: Vec<T>) -> Self {
+        v.sort();
+        v.dedup();
+        let tree = Self::from_sorted_vec(v);
+        tree.debug_validate(&T::cmp);
         tree
     }
 }
 
-impl<T> Tree234<T> {
+impl<T, const B: usize, M: Monoid<T>, L: Link> Default for BTree<T, B, M, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>, L: Link> BTree<T, B, M, L> {
     pub fn new() -> Self {
+        const { assert!(B >= 4 && B.is_multiple_of(2), "B should be an even number of at least 4") };
         Self {
-            data: [None, None, None, None],
-            children: [None, None, None, None],
-            size: 0,
+            data: ArrayVec::new(),
+            children: ArrayVec::new(),
+            length: 0,
+            aggregate: M::identity(),
         }
     }
 
@@ -96,35 +161,99 @@ impl<T> Tree234<T> {
     /// clear the tree.
     ///
     pub fn clear(&mut self) {
-        self.data = [None, None, None, None];
-        self.children = [None, None, None, None];
-        self.size = 0;
+        self.data.clear();
+        self.children.clear();
+        self.length = 0;
+        self.aggregate = M::identity();
     }
 
     ///
     /// check if the tree is empty.
     ///
     pub fn is_empty(&self) -> bool {
-        self.size == 0
+        self.data.is_empty()
+    }
+
+    ///
+    /// return the number of values in the tree in O(1).
+    ///
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    ///
+    /// return the k-th smallest value (0-indexed) in O(log n).
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![10, 30, 20]);
+    /// assert_eq!(tree.nth(0), Some(&10));
+    /// assert_eq!(tree.nth(2), Some(&30));
+    /// assert_eq!(tree.nth(3), None);
+    /// ```
+    ///
+    pub fn nth(&self, mut k: usize) -> Option<&T> {
+        if k >= self.length {
+            return None;
+        }
+        let mut node = self;
+        'outer: loop {
+            if node.is_leaf() {
+                return Some(node.key(k));
+            }
+            for i in 0..=node.size() {
+                let child = node.child(i);
+                if k < child.length {
+                    node = child;
+                    continue 'outer;
+                }
+                k -= child.length;
+                if k == 0 {
+                    return Some(node.key(i));
+                }
+                k -= 1;
+            }
+            unreachable!("length of the node is inconsistent with its children");
+        }
     }
 }
 
-impl<T: Ord> Tree234<T> {
+impl<T: Ord, const B: usize, M: Monoid<T>, L: LinkMut<BTree<T, B, M, L>>> BTree<T, B, M, L> {
     ///
     /// append all value in values to self.
+    /// values are sorted and built into a tree at once, which is joined to self in O(k log k + log n)
+    /// if no value is smaller than the values of self. otherwise they are inserted one by one in O(k log n).
     /// ```rust
     /// use algorithm::Tree234;
     /// let mut tree = Tree234::new();
     /// tree.append(vec![1, 2, 3]);
-    /// assert(tree.find(&1));
-    /// assert(tree.find(&2));
-    /// assert(tree.find(&3));
+    /// assert!(tree.find(&1));
+    /// assert!(tree.find(&2));
+    /// assert!(tree.find(&3));
     /// ```
     ///
     pub fn append(&mut self, values: Vec<T>) {
-        for i in values {
-            self.insert(i);
-        }
+        self.append_by(values, &T::cmp);
+        self.debug_validate(&T::cmp);
+    }
+
+    ///
+    /// make a tree from values in ascending order in O(n).
+    /// duplicated values are kept.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from_sorted_iter(vec![1, 2, 2, 3]);
+    /// assert_eq!(tree.len(), 4);
+    /// assert_eq!(tree.count(&2), 2);
+    /// ```
+    /// # Panics
+    /// panics if values are not sorted.
+    ///
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let values: Vec<T> = values.into_iter().collect();
+        assert!(values.is_sorted(), "values should be sorted in ascending order");
+        let tree = Self::from_sorted_vec(values);
+        tree.debug_validate(&T::cmp);
+        tree
     }
     ///
     /// insert value to self
@@ -136,133 +265,86 @@ impl<T: Ord> Tree234<T> {
     /// ```
     ///
     pub fn insert(&mut self, value: T) {
-        if self.size == 3 {
-            // 自分がrootかつsizeが3の場合のみ if以下はすべてinsertが呼び出される前に分割されるのでここには入らない.
-            let mid = self.data[1].take();
-            let left = Box::new(Self {
-                data: [self.data[0].take(), None, None, None],
-                children: [self.children[0].take(), self.children[1].take(), None, None],
-                size: 1,
-            });
-            let right = Box::new(Self {
-                data: [self.data[2].take(), None, None, None],
-                children: [self.children[2].take(), self.children[3].take(), None, None],
-                size: 1,
-            });
-            self.data = [mid, None, None, None];
-            self.children = [Some(left), Some(right), None, None];
-            self.size = 1;
-        }
-
-        let mut pos = self.find_index(&value);
-        if self.is_leaf() {
-            insert_to_array(&mut self.data, pos, Some(value));
-            self.size += 1;
-            return;
+        self.insert_by(value, &T::cmp, &mut Tracer::off());
+        self.debug_validate(&T::cmp);
+    }
+
+    /// 
+    /// insert value to self only if self does not contain it yet.
+    /// return true if value was inserted.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut tree = Tree234::new();
+    /// assert!(tree.insert_unique(1));
+    /// assert!(!tree.insert_unique(1));
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    /// 
+    pub fn insert_unique(&mut self, value: T) -> bool {
+        if self.find(&value) {
+            return false;
         }
+        self.insert(value);
+        true
+    }
 
-        if self.children[pos].as_ref().unwrap().size == 3 {
-            let mut child = delete_from_array(&mut self.children, pos).unwrap();
-            let mid = child.data[1].take();
-            let left = Box::new(Self {
-                data: [child.data[0].take(), None, None, None],
-                children: [
-                    child.children[0].take(),
-                    child.children[1].take(),
-                    None,
-                    None,
-                ],
-                size: 1,
-            });
-            let right = Box::new(Self {
-                data: [child.data[2].take(), None, None, None],
-                children: [
-                    child.children[2].take(),
-                    child.children[3].take(),
-                    None,
-                    None,
-                ],
-                size: 1,
-            });
-            let pos_ = self.find_index(mid.as_ref().unwrap());
-            insert_to_array(&mut self.data, pos_, mid);
-            insert_to_array(&mut self.children, pos_, Some(right));
-            insert_to_array(&mut self.children, pos_, Some(left));
-            self.size += 1;
-            pos = self.find_index(&value);
-        }
-        self.children[pos].as_mut().unwrap().insert(value);
-    }
-    /// 
-    /// if value is in self, delete it and return true. else return false
+    /// 
+    /// if value is in self, delete one occurrence of it and return true. else return false
     /// ```rust
     /// use algorithm::Tree234;
     /// let mut tree = Tree234::new();
     /// tree.insert(1);
-    /// assert(tree.delete(&1));
-    /// assert(!tree.delete(&1));
+    /// assert!(tree.delete(&1));
+    /// assert!(!tree.delete(&1));
     /// ```
     /// 
     pub fn delete(&mut self, value: &T) -> bool {
-        if self.is_leaf() {
-            let pos = self.find_index(value);
-            if pos < self.size && self.data[pos].as_ref().unwrap() == value {
-                delete_from_array(&mut self.data, pos);
-                self.size -= 1;
-                return true;
-            }
-            return false;
-        }
-        let pos = self.find_index(value);
-        let is_internal = pos < self.size && self.data[pos].as_ref().unwrap() == value;
-        if !is_internal && self.children[pos].as_ref().unwrap().size > 1 {
-            // いつかはここに引っかかるはず
-            // 子ノードの大きさが2以上の場合, それを起点に再帰的に削除を行う.
-            return self.children[pos].as_mut().unwrap().delete(value);
-        }
-        if !is_internal {
-            // internalでないかつ子ノードの大きさが1の場合
-            self.delete_balance(pos);
-            return self.delete(value);
-        }
-
-        self.delete_balance(pos);
-        // 内部ノードである場合, 通り道全体にrotateとかしてから左側最大と交換して削除する
-        let mut current = self.children[pos].as_mut().unwrap();
-        while !current.is_leaf() {
-            current.delete_balance(current.size);
-            current = current.children[current.size].as_mut().unwrap();
-        }
-        let pos = self.find_index(value);
-        let is_internal = pos < self.size && self.data[pos].as_ref().unwrap() == value;
-        if is_internal {
-            let mut current = self.children[pos].as_mut().unwrap();
-            while !current.is_leaf() {
-                current.delete_balance(current.size);
-                current = current.children[current.size].as_mut().unwrap();
-            }
-            std::mem::swap(&mut self.data[pos], &mut current.data[current.size - 1]);
-            return current.delete(value);
-        }
+        let deleted = self.delete_by(value, &T::cmp, &mut Tracer::off());
+        self.debug_validate(&T::cmp);
+        deleted
+    }
+
+    /// 
+    /// same as `delete`: delete one occurrence of value and return true if there was one.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut tree = Tree234::from_sorted_iter(vec![1, 1]);
+    /// assert!(tree.delete_one(&1));
+    /// assert_eq!(tree.count(&1), 1);
+    /// ```
+    /// 
+    pub fn delete_one(&mut self, value: &T) -> bool {
         self.delete(value)
     }
 
     /// 
-    /// make the node balanced for deletion.
+    /// delete every occurrence of value and return how many were deleted.
+    /// the occurrences are cut out with two splits and a join, so this takes O(log n + m) for m occurrences.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut tree = Tree234::from_sorted_iter(vec![1, 1, 1, 2]);
+    /// assert_eq!(tree.delete_all(&1), 3);
+    /// assert_eq!(tree.delete_all(&1), 0);
+    /// assert_eq!(tree.len(), 1);
+    /// ```
     /// 
-    fn delete_balance(&mut self, pos: usize) {
-        // 隣接兄弟ノードの大きさが2以上の場合, 回転を行う
-        if (pos > 0 && self.children[pos - 1].as_ref().unwrap().size > 1)
-            || (pos < self.size && self.children[pos + 1].as_ref().unwrap().size > 1)
-        {
-            self.rotate(pos);
-        } else if self.size > 1 {
-            // 隣接兄弟ノードの大きさが1で親要素の大きさが2以上の場合, マージを行う
-            self.merge(pos);
-        } else {
-            // 高さを1下げる.
-            self.shrink();
-        }
+    pub fn delete_all(&mut self, value: &T) -> usize {
+        let deleted = self.delete_all_by(value, &T::cmp);
+        self.debug_validate(&T::cmp);
+        deleted
+    }
+
+    /// 
+    /// return the number of occurrences of value in O(log n).
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from_sorted_iter(vec![1, 1, 2]);
+    /// assert_eq!(tree.count(&1), 2);
+    /// assert_eq!(tree.count(&3), 0);
+    /// ```
+    /// 
+    pub fn count(&self, value: &T) -> usize {
+        self.count_range(value..=value)
     }
 
     /// 
@@ -271,275 +353,1112 @@ impl<T: Ord> Tree234<T> {
     /// use algorithm::Tree234;
     /// let mut tree = Tree234::new();
     /// tree.insert(1);
-    /// assert(tree.find(&1));
-    /// assert(!tree.find(&2));
+    /// assert!(tree.find(&1));
+    /// assert!(!tree.find(&2));
     /// ```
     /// 
     pub fn find(&self, value: &T) -> bool {
-        let pos = self.find_index(value);
-        if pos < self.size && self.data[pos].as_ref().unwrap() == value {
-            return true;
-        }
-        if self.is_leaf() {
-            return false;
-        }
-        return self.children[pos].as_ref().unwrap().find(value);
+        self.find_by(value, &T::cmp)
     }
 
-    /// make self.children[pos] contains more than 1 element.
-    /// this should only be called when one of its sibling has more than 1 element.
-    fn rotate(&mut self, pos: usize) {
-        if pos > 0 && self.children[pos - 1].as_ref().unwrap().size > 1 {
-            // 左の兄弟から値を持ってくる
-            let brother_pos = pos - 1;
-            let parent_pos = pos - 1;
-            // data = [parent_data, self.data, None];
-            // children = [*brother.children, *self.children];
-            let parent_data = delete_from_array(&mut self.data, parent_pos);
-            insert_to_array(
-                &mut self.children[pos].as_mut().unwrap().data,
-                0,
-                parent_data,
-            );
-            let brother_size = self.children[brother_pos].as_mut().unwrap().size;
-            let brother_data = delete_from_array(
-                &mut self.children[brother_pos].as_mut().unwrap().data,
-                brother_size - 1,
-            );
-            insert_to_array(&mut self.data, parent_pos, brother_data);
-            let brother_child = delete_from_array(
-                &mut self.children[brother_pos].as_mut().unwrap().children,
-                brother_size,
-            );
-            let children = &mut self.children[pos].as_mut().unwrap().children;
-            insert_to_array(children, 0, brother_child);
-            self.children[brother_pos].as_mut().unwrap().size -= 1;
-            self.children[pos].as_mut().unwrap().size = 2;
-        } else {
-            // 右の兄弟から値を持ってくる
-            let brother_pos = pos + 1;
-            let parent_pos = pos;
-            // data = [self.data, parent_data, None];
-            // children = [*brother.children, *self.children];
-            let parent_data = delete_from_array(&mut self.data, parent_pos);
-            insert_to_array(
-                &mut self.children[pos].as_mut().unwrap().data,
-                1,
-                parent_data,
-            );
-            let brother_data =
-                delete_from_array(&mut self.children[brother_pos].as_mut().unwrap().data, 0);
-            insert_to_array(&mut self.data, parent_pos, brother_data);
-            let brother_child = delete_from_array(
-                &mut self.children[brother_pos].as_mut().unwrap().children,
-                0,
-            );
-            let size = self.children[pos].as_mut().unwrap().size;
-            let children = &mut self.children[pos].as_mut().unwrap().children;
-            insert_to_array(children, size, brother_child);
-            self.children[brother_pos].as_mut().unwrap().size -= 1;
-            self.children[pos].as_mut().unwrap().size = 2;
-        };
+    /// 
+    /// search the value and return (lower_bound, upper_bound)
+    /// lower_bound <= value <= upper_bound
+    /// if lower_bound is None, value is smaller than all of the values in the tree.
+    /// if upper_bound is None, value is larger than all of the values in the tree.
+    /// 
+    pub fn search_and_get_range(&self, value: &T) -> (Option<&T>, Option<&T>) {
+        (
+            self.upper_bound(Bound::Included(value)),
+            self.lower_bound(Bound::Included(value)),
+        )
     }
 
     /// 
-    /// make self.children[pos] contains more than 1 element.
-    /// this should only be called when self.size > 1 and all of its sibling has only 1 element.
-    /// 
-    fn merge(&mut self, pos: usize) {
-        // 兄弟要素の値と親要素のいい感じの値を自分のdataとし, 兄弟要素の子要素を自分の子要素とする.
-        self.size -= 1;
-        self.children[pos].as_mut().unwrap().size = 3;
-        if pos == 3 {
-            let brother_pos = pos - 1;
-            let parent_pos = pos - 1;
-            let parent_data = delete_from_array(&mut self.data, parent_pos);
-            let brother_data =
-                delete_from_array(&mut self.children[brother_pos].as_mut().unwrap().data, 0);
-            let data = &mut self.children[pos].as_mut().unwrap().data;
-            insert_to_array(data, 0, parent_data);
-            insert_to_array(data, 0, brother_data);
-            let brother_child0 = delete_from_array(
-                &mut self.children[brother_pos].as_mut().unwrap().children,
-                0,
-            );
-            let brother_child1 = delete_from_array(
-                &mut self.children[brother_pos].as_mut().unwrap().children,
-                0,
-            );
-            let children = &mut self.children[pos].as_mut().unwrap().children;
-            insert_to_array(children, 0, brother_child1);
-            insert_to_array(children, 0, brother_child0);
-        } else {
-            let brother_pos = pos + 1;
-            let parent_pos = pos;
-            let parent_data = delete_from_array(&mut self.data, parent_pos);
-            let brother_data =
-                delete_from_array(&mut self.children[brother_pos].as_mut().unwrap().data, 0);
-            let data = &mut self.children[pos].as_mut().unwrap().data;
-            data[2] = parent_data;
-            data[3] = brother_data;
-            let brother_child0 = delete_from_array(
-                &mut self.children[brother_pos].as_mut().unwrap().children,
-                0,
-            );
-            let brother_child1 = delete_from_array(
-                &mut self.children[brother_pos].as_mut().unwrap().children,
-                0,
-            );
-            let children = &mut self.children[pos].as_mut().unwrap().children;
-            children[2] = brother_child0;
-            children[3] = brother_child1;
-        }
+    /// return the smallest value which lies above the bound.
+    /// `Included(x)` looks for a value >= x, `Excluded(x)` for a value > x.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// use std::ops::Bound;
+    /// let tree = Tree234::from(vec![1, 3, 5]);
+    /// assert_eq!(tree.lower_bound(Bound::Included(&3)), Some(&3));
+    /// assert_eq!(tree.lower_bound(Bound::Excluded(&3)), Some(&5));
+    /// assert_eq!(tree.lower_bound(Bound::Excluded(&5)), None);
+    /// ```
+    /// 
+    pub fn lower_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        self.lower_bound_by(bound, &T::cmp)
     }
 
     /// 
-    /// make self.size == 1 and all of its sibling has only 1 element.
-    /// this should only be called when self.size == 1 and all of its sibling has only 1 element.
+    /// return the largest value which lies below the bound.
+    /// `Included(x)` looks for a value <= x, `Excluded(x)` for a value < x.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// use std::ops::Bound;
+    /// let tree = Tree234::from(vec![1, 3, 5]);
+    /// assert_eq!(tree.upper_bound(Bound::Included(&3)), Some(&3));
+    /// assert_eq!(tree.upper_bound(Bound::Excluded(&3)), Some(&1));
+    /// assert_eq!(tree.upper_bound(Bound::Excluded(&1)), None);
+    /// ```
     /// 
-    fn shrink(&mut self) {
-        // self.data = [left.data, self.data, right.data, None];
-        // self.children = [*left.children, *right.children];
-        self.size = 3;
-        insert_to_array(
-            &mut self.data,
-            0,
-            self.children[0].as_mut().unwrap().data[0].take(),
-        );
-        self.data[2] = self.children[1].as_mut().unwrap().data[0].take();
-        assert!(self.children[1].as_mut().unwrap().children[0].is_some());
-        self.children[3] = self.children[1].as_mut().unwrap().children[1].take();
-        self.children[2] = self.children[1].as_mut().unwrap().children[0].take();
-        self.children[1] = self.children[0].as_mut().unwrap().children[1].take();
-        self.children[0] = self.children[0].as_mut().unwrap().children[0].take();
-    }
-
-    /// 
-    /// find the index of self's child which may contains value
-    /// if value is in self.data, return the index of self.data
+    pub fn upper_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        self.upper_bound_by(bound, &T::cmp)
+    }
+
     /// 
-    fn find_index(&self, value: &T) -> usize {
-        // 挿入する場合, どの位置に挿入するべきかを返す.
-        for i in 0..self.size {
-            if value <= self.data[i].as_ref().unwrap() {
-                return i;
-            }
-        }
-        self.size
+    /// return the largest value which is smaller than value.
+    /// 
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        self.upper_bound(Bound::Excluded(value))
+    }
+
+    /// 
+    /// return the smallest value which is larger than value.
+    /// 
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        self.lower_bound(Bound::Excluded(value))
+    }
+
+    /// 
+    /// return the number of values which are smaller than value in O(log n).
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![10, 20, 30]);
+    /// assert_eq!(tree.rank(&20), 1);
+    /// assert_eq!(tree.rank(&25), 2);
+    /// ```
+    /// 
+    pub fn rank(&self, value: &T) -> usize {
+        self.count_range(..value)
+    }
+
+    /// 
+    /// return the number of values inside range in O(log n).
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.count_range(2..4), 2);
+    /// assert_eq!(tree.count_range(2..), 4);
+    /// ```
+    /// 
+    pub fn count_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        self.count_range_by(range, &T::cmp)
+    }
+
+    /// 
+    /// iterate over the values inside range in ascending order.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.range(2..4).collect::<Vec<_>>(), vec![&2, &3]);
+    /// assert_eq!(tree.range(..=2).collect::<Vec<_>>(), vec![&1, &2]);
+    /// ```
+    /// 
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T, B, M, L> {
+        self.range_by(range, &T::cmp)
+    }
+
+    /// 
+    /// split the tree at value in O(log n).
+    /// self keeps the values smaller than value, and the rest are returned.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut tree = Tree234::from(vec![1, 2, 3, 4, 5]);
+    /// let right = tree.split_off(&3);
+    /// assert_eq!(tree.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    /// assert_eq!(right.iter().collect::<Vec<_>>(), vec![&3, &4, &5]);
+    /// ```
+    /// 
+    pub fn split_off(&mut self, value: &T) -> Self {
+        let right = self.split_off_by(value, &T::cmp);
+        self.debug_validate(&T::cmp);
+        right.debug_validate(&T::cmp);
+        right
+    }
+
+    /// 
+    /// move all values of other after the values of self in O(log n).
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut tree = Tree234::from(vec![1, 2]);
+    /// tree.append_tree(Tree234::from(vec![3, 4, 5]));
+    /// assert_eq!(tree.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4, &5]);
+    /// ```
+    /// # Panics
+    /// panics if a value of self is larger than a value of other.
+    /// 
+    pub fn append_tree(&mut self, other: Self) {
+        self.append_tree_by(other, &T::cmp);
+        self.debug_validate(&T::cmp);
+    }
+
+    /// 
+    /// remove the smallest value from the tree and return it.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut tree = Tree234::from(vec![2, 1, 3]);
+    /// assert_eq!(tree.pop_first(), Some(1));
+    /// assert_eq!(tree.pop_first(), Some(2));
+    /// ```
+    /// 
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.pop_first_by(&T::cmp)
+    }
+
+    /// 
+    /// remove the largest value from the tree and return it.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut tree = Tree234::from(vec![2, 1, 3]);
+    /// assert_eq!(tree.pop_last(), Some(3));
+    /// assert_eq!(tree.pop_last(), Some(2));
+    /// ```
+    /// 
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.pop_last_by(&T::cmp)
+    }
+
+    /// 
+    /// concatenate left and right in O(log n).
+    /// # Panics
+    /// panics if a value of left is larger than a value of right.
+    /// 
+    pub fn join(mut left: Self, right: Self) -> Self {
+        left.append_tree(right);
+        left
     }
 }
 
-impl<T: Ord + Clone> Tree234<T> {
+/// 
+/// the search algorithms are written against a comparator so that
+/// both `Tree234` (ordered by `Ord`) and `Tree234By` share them.
+/// 
+impl<T, const B: usize, M: Monoid<T>, L: LinkMut<BTree<T, B, M, L>>> BTree<T, B, M, L> {
     /// 
-    /// search the value and return (lower_bound, upper_bound)
-    /// lower_bound <= value <= upper_bound
-    /// if lower_bound is None, value is smaller than all of the values in the tree.
-    /// if upper_bound is None, value is larger than all of the values in the tree.
+    /// insert value to self in the order given by cmp, reporting each step to tracer.
     /// 
-    pub fn search_and_get_range(&self, value: &T) -> (Option<&T>, Option<&T>) {
-        if self.is_empty() {
-            return (None, None);
+    pub(crate) fn insert_by<C: Compare<T>>(&mut self, value: T, cmp: &C, tracer: &mut Tracer<T, B, M, L>) {
+        if self.is_full() {
+            // 自分がrootかつ満杯の場合のみ if以下はすべてinsertが呼び出される前に分割されるのでここには入らない.
+            self.split_root();
+            tracer.step(self, |path| TraceEvent::Split { path });
         }
+
+        let mut pos = self.find_index(&value, cmp);
         if self.is_leaf() {
-            let mut left = None;
-            let mut right = None;
-            for i in 0..self.size {
-                if self.data[i].as_ref().unwrap() <= value {
-                    left = Some(self.data[i].as_ref().unwrap());
-                }
-                if self.data[i].as_ref().unwrap() >= value {
-                    right = Some(self.data[i].as_ref().unwrap());
-                    break;
-                }
+            self.data.insert(pos, value);
+            self.update();
+            tracer.step(self, |path| TraceEvent::Insert { path, pos });
+            return;
+        }
+
+        if self.child(pos).is_full() {
+            self.split_child(pos);
+            tracer.step(self, |path| TraceEvent::Split { path });
+            if cmp.compare(&value, self.key(pos)).is_gt() {
+                pos += 1;
             }
-            return (left, right);
         }
+        tracer.enter(pos);
+        self.child_mut(pos).insert_by(value, cmp, tracer);
+        tracer.leave();
+        self.update();
+    }
 
-        for i in 0..self.size {
-            if value < self.data[i].as_ref().unwrap() {
-                let mut ans = self.children[i]
-                    .as_ref()
-                    .unwrap()
-                    .search_and_get_range(value);
-                if ans.1.is_none() {
-                    ans.1 = self.data[i].as_ref();
+    /// 
+    /// append all value in values to self in the order given by cmp.
+    /// 
+    pub(crate) fn append_by<C: Compare<T>>(&mut self, mut values: Vec<T>, cmp: &C) {
+        values.sort_by(|a, b| cmp.compare(a, b));
+        let other = Self::from_sorted_vec(values);
+        match (self.last(), other.first()) {
+            // 全ての値がselfより後ろに来る場合はまとめて構築してつなげる.
+            (None, _) | (_, None) => self.append_tree_unchecked(other),
+            (Some(last), Some(first)) if cmp.compare(last, first).is_le() => self.append_tree_unchecked(other),
+            _ => {
+                for i in other.into_sorted_vec() {
+                    self.insert_by(i, cmp, &mut Tracer::off());
                 }
-                return ans;
             }
         }
-        let mut ans = self.children[self.size]
-            .as_ref()
-            .unwrap()
-            .search_and_get_range(value);
-        if ans.0.is_none() {
-            ans.0 = self.data[self.size - 1].as_ref();
+    }
+
+    /// 
+    /// delete one occurrence of value in the order given by cmp, reporting each step to tracer.
+    /// 
+    pub(crate) fn delete_by<C: Compare<T>>(&mut self, value: &T, cmp: &C, tracer: &mut Tracer<T, B, M, L>) -> bool {
+        let pos = self.find_index(value, cmp);
+        let found = pos < self.size() && cmp.compare(self.key(pos), value).is_eq();
+        if self.is_leaf() {
+            if found {
+                self.data.remove(pos);
+                self.update();
+                tracer.step(self, |path| TraceEvent::Remove { path, pos });
+            }
+            return found;
+        }
+        if self.child(pos).is_sparse() {
+            // 子ノードの値が少ない場合, 回転・マージ・縮小で増やしてから探しなおす.
+            self.delete_balance(pos, tracer);
+            return self.delete_by(value, cmp, tracer);
+        }
+        if found {
+            // 内部ノードである場合, 左の部分木の最大値で置き換える.
+            tracer.enter(pos);
+            let predecessor = self.child_mut(pos).pop_last_unchecked(tracer);
+            tracer.leave();
+            self.data[pos] = predecessor;
+            self.update();
+            tracer.step(self, |path| TraceEvent::SwapWithPredecessor { path, pos });
+            return true;
+        }
+        tracer.enter(pos);
+        let deleted = self.child_mut(pos).delete_by(value, cmp, tracer);
+        tracer.leave();
+        if deleted {
+            self.update();
         }
-        return ans;
+        deleted
     }
-}
 
-impl<T> Tree234<T> {
     /// 
-    /// check if self is a leaf node.
+    /// remove the smallest value, and check the tree in the order given by cmp.
     /// 
-    pub(crate) fn is_leaf(&self) -> bool {
-        self.children[0].is_none()
+    pub(crate) fn pop_first_by<C: Compare<T>>(&mut self, cmp: &C) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = self.pop_first_unchecked();
+        self.debug_validate(cmp);
+        Some(value)
     }
-}
 
-/// 
-/// Insert value to array[index], shifting the rest of the array to the right.
-/// 
-fn insert_to_array<S>(array: &mut [S], index: usize, value: S) {
-    #[cold]
-    #[cfg_attr(not(feature = "panic_immediate_abort"), inline(never))]
-    #[track_caller]
-    fn assert_failed(index: usize, len: usize) -> ! {
-        panic!("insertion index (is {index}) should be <= len (is {len})");
-    }
-    let len = 3;
-
-    if index > len {
-        assert_failed(index, len);
-    }
-
-    unsafe {
-        // infallible
-        // The spot to put the new value
-        let p = array.as_mut_ptr().add(index);
-        // Shift everything over to make space. (Duplicating the
-        // `index`th element into two consecutive places.)
-        ptr::copy(p, p.add(1), len - index);
-        // Write it in, overwriting the first copy of the `index`th
-        // element.
-        ptr::write(p, value);
+    /// 
+    /// remove the largest value, and check the tree in the order given by cmp.
+    /// 
+    pub(crate) fn pop_last_by<C: Compare<T>>(&mut self, cmp: &C) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = self.pop_last_unchecked(&mut Tracer::off());
+        self.debug_validate(cmp);
+        Some(value)
     }
-}
 
+    /// 
+    /// delete every occurrence of value in the order given by cmp, by splitting them off and joining the rest.
+    /// 
+    pub(crate) fn delete_all_by<C: Compare<T>>(&mut self, value: &T, cmp: &C) -> usize {
+        if !self.find_by(value, cmp) {
+            return 0;
+        }
+        let mut middle = self.split_off_by(value, cmp);
+        // 等しい値も左に入るように比べて, valueより大きい部分を切り離す.
+        let right = middle.split_off_by(value, &|x: &T, y: &T| cmp.compare(x, y).then(Ordering::Less));
+        self.append_tree_unchecked(right);
+        middle.len()
+    }
+
+    /// 
+    /// split the tree at value in the order given by cmp.
+    /// 
+    pub(crate) fn split_off_by<C: Compare<T>>(&mut self, value: &T, cmp: &C) -> Self {
+        let height = self.height();
+        let ((left, _), (right, _)) = std::mem::take(self).split_by(value, cmp, height);
+        *self = left;
+        right
+    }
+
+    /// 
+    /// move all values of other after the values of self, checking the order with cmp.
+    /// 
+    pub(crate) fn append_tree_by<C: Compare<T>>(&mut self, other: Self, cmp: &C) {
+        if let (Some(last), Some(first)) = (self.last(), other.first()) {
+            assert!(
+                cmp.compare(last, first).is_le(),
+                "every value of the left tree should be <= the values of the right tree"
+            );
+        }
+        self.append_tree_unchecked(other);
+    }
+
+    /// 
+    /// split the subtree rooted at self, whose height is height, into the values smaller than value and the rest.
+    /// both parts are returned with their heights, so that no height is measured again on the way up.
+    /// 
+    fn split_by<C: Compare<T>>(self, value: &T, cmp: &C, height: usize) -> ((Self, usize), (Self, usize)) {
+        // 分割する位置の子を再帰的に分割し, 左右の残りとjoinする.
+        let pos = self.find_index(value, cmp);
+        let Self { mut data, mut children, .. } = self;
+        let mut right_data = data.split_off(pos);
+        if children.is_empty() {
+            return (Self::from_nodes(data, children, height), Self::from_nodes(right_data, ArrayVec::new(), height));
+        }
+        let right_children = children.split_off(pos + 1);
+        let child = L::into_inner(children.pop().unwrap());
+        let ((mut left, mut left_height), (mut right, mut right_height)) = child.split_by(value, cmp, height - 1);
+        if let Some(mid) = data.pop() {
+            let (mut rest, rest_height) = Self::from_nodes(data, children, height);
+            left_height = rest.join3(rest_height, mid, left, left_height);
+            left = rest;
+        }
+        if !right_data.is_empty() {
+            let mid = right_data.remove(0);
+            let (rest, rest_height) = Self::from_nodes(right_data, right_children, height);
+            right_height = right.join3(right_height, mid, rest, rest_height);
+        }
+        ((left, left_height), (right, right_height))
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>, L: Link> BTree<T, B, M, L> {
+    /// 
+    /// check if self contains value in the order given by cmp.
+    /// 
+    pub(crate) fn find_by<C: Compare<T>>(&self, value: &T, cmp: &C) -> bool {
+        let pos = self.find_index(value, cmp);
+        if pos < self.size() && cmp.compare(self.key(pos), value).is_eq() {
+            return true;
+        }
+        if self.is_leaf() {
+            return false;
+        }
+        self.child(pos).find_by(value, cmp)
+    }
+
+    /// 
+    /// return the smallest value which lies above the bound in the order given by cmp.
+    /// 
+    pub(crate) fn lower_bound_by<C: Compare<T>>(&self, bound: Bound<&T>, cmp: &C) -> Option<&T> {
+        let pos = self.index_after(bound, cmp);
+        let here = (pos < self.size()).then(|| self.key(pos));
+        if self.is_leaf() {
+            return here;
+        }
+        self.child(pos).lower_bound_by(bound, cmp).or(here)
+    }
+
+    /// 
+    /// return the largest value which lies below the bound in the order given by cmp.
+    /// 
+    pub(crate) fn upper_bound_by<C: Compare<T>>(&self, bound: Bound<&T>, cmp: &C) -> Option<&T> {
+        let pos = self.index_before(bound, cmp);
+        let here = (pos > 0).then(|| self.key(pos - 1));
+        if self.is_leaf() {
+            return here;
+        }
+        self.child(pos).upper_bound_by(bound, cmp).or(here)
+    }
+
+    /// 
+    /// return the number of values inside range in the order given by cmp.
+    /// 
+    pub(crate) fn count_range_by<C: Compare<T>, R: RangeBounds<T>>(&self, range: R, cmp: &C) -> usize {
+        let before_start = match range.start_bound() {
+            Bound::Included(start) => self.count_while(&|x| cmp.compare(x, start).is_lt()),
+            Bound::Excluded(start) => self.count_while(&|x| cmp.compare(x, start).is_le()),
+            Bound::Unbounded => 0,
+        };
+        let before_end = match range.end_bound() {
+            Bound::Included(end) => self.count_while(&|x| cmp.compare(x, end).is_le()),
+            Bound::Excluded(end) => self.count_while(&|x| cmp.compare(x, end).is_lt()),
+            Bound::Unbounded => self.length,
+        };
+        before_end.saturating_sub(before_start)
+    }
+
+    /// 
+    /// iterate over the values inside range in the order given by cmp.
+    /// 
+    pub(crate) fn range_by<C: Compare<T>, R: RangeBounds<T>>(&self, range: R, cmp: &C) -> Range<'_, T, B, M, L> {
+        let first = self.lower_bound_by(range.start_bound(), cmp);
+        let mut last = self.upper_bound_by(range.end_bound(), cmp);
+        if matches!((first, last), (Some(first), Some(last)) if cmp.compare(first, last).is_gt()) {
+            last = None;
+        }
+        let mut iter = Iter { stack: vec![] };
+        let mut node = self;
+        loop {
+            let pos = node.index_after(range.start_bound(), cmp);
+            iter.stack.push((node, pos));
+            if node.is_leaf() {
+                break;
+            }
+            node = node.child(pos);
+        }
+        Range { iter, last }
+    }
+
+    /// if value is in self.data, return the index of self.data
+    /// 
+    pub(crate) fn find_index<C: Compare<T>>(&self, value: &T, cmp: &C) -> usize {
+        // 挿入する場合, どの位置に挿入するべきかを返す.
+        self.partition(|x| cmp.compare(x, value).is_lt())
+    }
+
+    /// 
+    /// number of self.data which lie before the bound as a lower bound.
+    /// 
+    pub(crate) fn index_after<C: Compare<T>>(&self, bound: Bound<&T>, cmp: &C) -> usize {
+        match bound {
+            Bound::Included(value) => self.partition(|x| cmp.compare(x, value).is_lt()),
+            Bound::Excluded(value) => self.partition(|x| cmp.compare(x, value).is_le()),
+            Bound::Unbounded => 0,
+        }
+    }
+
+    /// 
+    /// number of self.data which lie inside the bound as an upper bound.
+    /// 
+    fn index_before<C: Compare<T>>(&self, bound: Bound<&T>, cmp: &C) -> usize {
+        match bound {
+            Bound::Included(value) => self.partition(|x| cmp.compare(x, value).is_le()),
+            Bound::Excluded(value) => self.partition(|x| cmp.compare(x, value).is_lt()),
+            Bound::Unbounded => self.size(),
+        }
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>, L: Link> BTree<T, B, M, L> {
+    /// 
+    /// return the smallest value in the tree.
+    /// 
+    pub fn first(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut node = self;
+        while !node.is_leaf() {
+            node = node.child(0);
+        }
+        Some(node.key(0))
+    }
+
+    /// 
+    /// return the largest value in the tree.
+    /// 
+    pub fn last(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut node = self;
+        while !node.is_leaf() {
+            node = node.child(node.size());
+        }
+        Some(node.key(node.size() - 1))
+    }
+
+    /// 
+    /// iterate over the values in ascending order.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![3, 1, 2]);
+    /// assert_eq!(tree.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    /// 
+    pub fn iter(&self) -> Iter<'_, T, B, M, L> {
+        let mut iter = Iter { stack: vec![] };
+        iter.push_leftmost(self);
+        iter
+    }
+
+    /// 
+    /// build a tree from values which are already sorted in O(n).
+    /// 
+    pub(crate) fn from_sorted_vec(values: Vec<T>) -> Self {
+        let len = values.len();
+        // 高さhの木には最大でB^h - 1個の値が入るので, それに収まる最小の高さで作る.
+        let mut height = 0;
+        while Self::max_len(height) < len {
+            height += 1;
+        }
+        Self::build(&mut values.into_iter(), len, height)
+    }
+
+    /// 
+    /// build a tree of exactly height levels from the next len values of values.
+    /// len must be between 2^height - 1 and B^height - 1.
+    /// 
+    fn build(values: &mut impl Iterator<Item = T>, len: usize, height: usize) -> Self {
+        if height <= 1 {
+            return Self::from_parts(values.take(len).collect(), vec![]);
+        }
+        // 子の数をできるだけ少なくし, 値を子に均等に配る.
+        let capacity = Self::max_len(height - 1);
+        let width = (2..=B).find(|&c| len - (c - 1) <= c * capacity).unwrap();
+        let (base, rem) = ((len - (width - 1)) / width, (len - (width - 1)) % width);
+        let mut data = Vec::with_capacity(width - 1);
+        let mut children = Vec::with_capacity(width);
+        for i in 0..width {
+            let child_len = base + usize::from(i < rem);
+            children.push(Self::build(values, child_len, height - 1));
+            if i + 1 < width {
+                data.push(values.next().unwrap());
+            }
+        }
+        Self::from_parts(data, children)
+    }
+
+    /// 
+    /// return the number of levels of the tree. an empty tree has height 0.
+    /// 
+    pub fn height(&self) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut node = self;
+        let mut height = 1;
+        while !node.is_leaf() {
+            node = node.child(0);
+            height += 1;
+        }
+        height
+    }
+
+    /// 
+    /// make a node from its values and children.
+    /// a node without values is replaced by its only child, if any.
+    /// 
+    fn from_parts(data: Vec<T>, children: Vec<Self>) -> Self {
+        if data.is_empty() {
+            return children.into_iter().next().unwrap_or_default();
+        }
+        let mut node = Self::new();
+        node.data = data.into_iter().collect();
+        node.children = children.into_iter().map(L::new).collect();
+        node.update();
+        node
+    }
+
+    /// 
+    /// recompute the values cached in self from its data and children.
+    /// 
+    pub(crate) fn update(&mut self) {
+        self.length = self.size() + self.children.iter().map(|child| child.length).sum::<usize>();
+        // 子と値を昇順に並べた順で積をとる.
+        let mut aggregate = self.children.iter().next().map_or_else(M::identity, |child| child.aggregate.clone());
+        for i in 0..self.size() {
+            aggregate = M::op(&aggregate, &M::single(self.key(i)));
+            if !self.is_leaf() {
+                aggregate = M::op(&aggregate, &self.child(i + 1).aggregate);
+            }
+        }
+        self.aggregate = aggregate;
+    }
+
+    /// 
+    /// number of values in the subtree which satisfy pred.
+    /// pred must hold for a prefix of the values in ascending order.
+    /// 
+    pub(crate) fn count_while(&self, pred: &impl Fn(&T) -> bool) -> usize {
+        let pos = self.partition(pred);
+        if self.is_leaf() {
+            return pos;
+        }
+        let before: usize = (0..pos).map(|i| self.child(i).length).sum();
+        pos + before + self.child(pos).count_while(pred)
+    }
+
+    /// 
+    /// number of leading self.data which satisfy pred.
+    /// 
+    fn partition(&self, pred: impl Fn(&T) -> bool) -> usize {
+        (0..self.size()).take_while(|&i| pred(self.key(i))).count()
+    }
+
+    /// 
+    /// check if self is a leaf node.
+    /// 
+    pub(crate) fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// 
+    /// number of values in the node.
+    /// 
+    pub(crate) fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// 
+    /// check if the node has B - 1 values, so it must be split before a value is added below it.
+    /// 
+    pub(crate) fn is_full(&self) -> bool {
+        self.size() == B - 1
+    }
+
+    /// 
+    /// check if the node has at most B / 2 - 1 values, so it cannot lose a value.
+    /// 
+    pub(crate) fn is_sparse(&self) -> bool {
+        self.size() < B / 2
+    }
+
+    /// 
+    /// the maximum number of values a tree of the given height can hold: B^height - 1.
+    /// 
+    fn max_len(height: usize) -> usize {
+        B.saturating_pow(height as u32) - 1
+    }
+
+    pub(crate) fn key(&self, pos: usize) -> &T {
+        &self.data[pos]
+    }
+
+    pub(crate) fn child(&self, pos: usize) -> &Self {
+        &self.children[pos]
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>, L: LinkMut<BTree<T, B, M, L>>> BTree<T, B, M, L> {
+    /// 
+    /// take all values out of the tree in ascending order.
+    /// 
+    pub(crate) fn into_sorted_vec(self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.length);
+        self.drain_into(&mut values);
+        values
+    }
+
+    fn drain_into(self, values: &mut Vec<T>) {
+        let mut children = self.children.into_iter();
+        for value in self.data {
+            if let Some(child) = children.next() {
+                L::into_inner(child).drain_into(values);
+            }
+            values.push(value);
+        }
+        if let Some(child) = children.next() {
+            L::into_inner(child).drain_into(values);
+        }
+    }
+
+    /// 
+    /// move all values of other after the values of self without checking their order.
+    /// 
+    pub(crate) fn append_tree_unchecked(&mut self, mut other: Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            *self = other;
+            return;
+        }
+        let mid = other.pop_first_unchecked();
+        let (height, other_height) = (self.height(), other.height());
+        self.join3(height, mid, other, other_height);
+    }
+
+    /// 
+    /// concatenate self, mid and right in O(|height - right_height| + 1),
+    /// where height and right_height are the heights of self and right. return the height of the result.
+    /// every value of self must be <= mid <= every value of right.
+    /// 
+    fn join3(&mut self, mut height: usize, mid: T, mut right: Self, right_height: usize) -> usize {
+        if height == right_height {
+            // 高さが同じならmidを根とする.
+            let left = std::mem::take(self);
+            let children = if height == 0 { vec![] } else { vec![left, right] };
+            *self = Self::from_parts(vec![mid], children);
+            return height + 1;
+        }
+        let left_is_higher = height > right_height;
+        if !left_is_higher {
+            std::mem::swap(self, &mut right);
+        }
+        let (low, low_height) = (right, height.min(right_height));
+        height = height.max(right_height);
+        if self.is_full() {
+            self.split_root();
+            height += 1;
+        }
+        // 低い方の木を高い方の木の端の, 高さの合う位置に差し込む.
+        let low = (!low.is_empty()).then(|| L::new(low));
+        if left_is_higher {
+            self.push_back(mid, low, height - low_height);
+        } else {
+            self.push_front(mid, low, height - low_height);
+        }
+        height
+    }
+
+    /// 
+    /// put mid and right after all values of self, where right is depth levels lower than self.
+    /// this should only be called when self is not full.
+    /// 
+    fn push_back(&mut self, mid: T, right: Option<L::Ptr<Self>>, depth: usize) {
+        if depth == 1 {
+            self.data.push(mid);
+            self.children.extend(right);
+        } else {
+            let mut pos = self.size();
+            if self.child(pos).is_full() {
+                self.split_child(pos);
+                pos += 1;
+            }
+            self.child_mut(pos).push_back(mid, right, depth - 1);
+        }
+        self.update();
+    }
+
+    /// 
+    /// put left and mid before all values of self, where left is depth levels lower than self.
+    /// this should only be called when self is not full.
+    /// 
+    fn push_front(&mut self, mid: T, left: Option<L::Ptr<Self>>, depth: usize) {
+        if depth == 1 {
+            self.data.insert(0, mid);
+            if let Some(left) = left {
+                self.children.insert(0, left);
+            }
+        } else {
+            if self.child(0).is_full() {
+                self.split_child(0);
+            }
+            self.child_mut(0).push_front(mid, left, depth - 1);
+        }
+        self.update();
+    }
+
+    /// 
+    /// make a node from its values and children, and return it with its height,
+    /// where height is the height the node would have with values.
+    /// a node without values is replaced by its only child, if any.
+    /// 
+    fn from_nodes(data: ArrayVec<T, B>, mut children: ArrayVec<L::Ptr<Self>, B>, height: usize) -> (Self, usize) {
+        if data.is_empty() {
+            return match children.pop() {
+                Some(child) => (L::into_inner(child), height - 1),
+                None => (Self::new(), 0),
+            };
+        }
+        let mut node = Self::new();
+        node.data = data;
+        node.children = children;
+        node.update();
+        (node, height)
+    }
+
+    /// 
+    /// insert value so that it becomes the k-th smallest value (0-indexed) of self.
+    /// the caller must keep the values sorted, and k must be at most self.len().
+    /// 
+    pub(crate) fn insert_at(&mut self, mut k: usize, value: T) {
+        if self.is_full() {
+            self.split_root();
+        }
+        if self.is_leaf() {
+            self.data.insert(k, value);
+            self.update();
+            return;
+        }
+        // 子の末尾への挿入はその子で行う.
+        let mut pos = 0;
+        while k > self.child(pos).length {
+            k -= self.child(pos).length + 1;
+            pos += 1;
+        }
+        if self.child(pos).is_full() {
+            self.split_child(pos);
+            let left = self.child(pos).length;
+            if k > left {
+                k -= left + 1;
+                pos += 1;
+            }
+        }
+        self.child_mut(pos).insert_at(k, value);
+        self.update();
+    }
+
+    /// 
+    /// remove the k-th smallest value (0-indexed) of self. k must be less than self.len().
+    /// 
+    pub(crate) fn remove_at(&mut self, k: usize) -> T {
+        if self.is_leaf() {
+            let value = self.data.remove(k);
+            self.update();
+            return value;
+        }
+        // k番目の値を含む子, またはk番目の値そのものの位置を探す.
+        let (mut pos, mut rest) = (0, k);
+        let is_key = loop {
+            let length = self.child(pos).length;
+            if rest < length {
+                break false;
+            }
+            if rest == length {
+                break true;
+            }
+            rest -= length + 1;
+            pos += 1;
+        };
+        if self.child(pos).is_sparse() {
+            self.delete_balance(pos, &mut Tracer::off());
+            return self.remove_at(k);
+        }
+        let value = if is_key {
+            // 内部ノードである場合, 左の部分木の最大値で置き換える.
+            let predecessor = self.child_mut(pos).pop_last_unchecked(&mut Tracer::off());
+            std::mem::replace(&mut self.data[pos], predecessor)
+        } else {
+            self.child_mut(pos).remove_at(rest)
+        };
+        self.update();
+        value
+    }
+
+    /// 
+    /// remove the smallest value of self.
+    /// self must not be empty, and must contain more than 1 element unless it is the root.
+    /// 
+    fn pop_first_unchecked(&mut self) -> T {
+        if self.is_leaf() {
+            let first = self.data.remove(0);
+            self.update();
+            return first;
+        }
+        if self.child(0).is_sparse() {
+            self.delete_balance(0, &mut Tracer::off());
+            return self.pop_first_unchecked();
+        }
+        let first = self.child_mut(0).pop_first_unchecked();
+        self.update();
+        first
+    }
+
+    /// 
+    /// remove the largest value of self, reporting each step to tracer.
+    /// self must not be empty, and must contain more than 1 element unless it is the root.
+    /// 
+    fn pop_last_unchecked(&mut self, tracer: &mut Tracer<T, B, M, L>) -> T {
+        if self.is_leaf() {
+            let last = self.data.pop().unwrap();
+            self.update();
+            let pos = self.size();
+            tracer.step(self, |path| TraceEvent::Remove { path, pos });
+            return last;
+        }
+        let size = self.size();
+        if self.child(size).is_sparse() {
+            self.delete_balance(size, tracer);
+            return self.pop_last_unchecked(tracer);
+        }
+        tracer.enter(size);
+        let last = self.child_mut(size).pop_last_unchecked(tracer);
+        tracer.leave();
+        self.update();
+        last
+    }
+
+    /// 
+    /// split the root which is full, making the tree 1 level higher.
+    /// 
+    pub(crate) fn split_root(&mut self) {
+        let (mid, left, right) = self.split_full();
+        self.data.push(mid);
+        self.children.push(left);
+        self.children.push(right);
+        self.update();
+    }
+
+    /// 
+    /// split self.children[pos] which is full, and move its middle value to self.
+    /// this should only be called when self is not full.
+    /// 
+    pub(crate) fn split_child(&mut self, pos: usize) {
+        let (mid, left, right) = self.child_mut(pos).split_full();
+        // 重複した値がある場合も分割した子の位置に中央の値を入れる.
+        self.data.insert(pos, mid);
+        self.children[pos] = left;
+        self.children.insert(pos + 1, right);
+    }
+
+    /// 
+    /// take apart a node which has B - 1 values into its middle value and two nodes with B / 2 - 1 values.
+    /// self is left empty.
+    /// 
+    fn split_full(&mut self) -> (T, L::Ptr<Self>, L::Ptr<Self>) {
+        let mut right = Self::new();
+        right.data = self.data.split_off(B / 2);
+        if !self.is_leaf() {
+            right.children = self.children.split_off(B / 2);
+        }
+        let mid = self.data.pop().unwrap();
+        let mut left = std::mem::take(self);
+        left.update();
+        right.update();
+        (mid, L::new(left), L::new(right))
+    }
+
+    /// 
+    /// make the node balanced for deletion, reporting the step to tracer.
+    /// 
+    pub(crate) fn delete_balance(&mut self, pos: usize, tracer: &mut Tracer<T, B, M, L>) {
+        // 隣接兄弟ノードに値の余裕がある場合, 回転を行う
+        if (pos > 0 && !self.child(pos - 1).is_sparse())
+            || (pos < self.size() && !self.child(pos + 1).is_sparse())
+        {
+            let direction = self.rotate(pos);
+            tracer.step(self, |path| TraceEvent::Rotate { path, pos, direction });
+        } else if self.size() > 1 {
+            // 隣接兄弟ノードに余裕がなく親要素の大きさが2以上の場合, マージを行う
+            let left_pos = self.merge(pos);
+            tracer.step(self, |path| TraceEvent::Merge { path, pos: left_pos });
+        } else {
+            // 高さを1下げる.
+            self.shrink();
+            tracer.step(self, |path| TraceEvent::Shrink { path });
+        }
+    }
+
+    /// make self.children[pos] not sparse by borrowing a value through self, and return the way it moved.
+    /// this should only be called when one of its sibling is not sparse.
+    fn rotate(&mut self, pos: usize) -> RotateDirection {
+        if pos > 0 && !self.child(pos - 1).is_sparse() {
+            // 左の兄弟から値を持ってくる
+            // child.data = [parent_data, *child.data];
+            // child.children = [brother.children.last, *child.children];
+            let brother = self.child_mut(pos - 1);
+            let brother_data = brother.data.pop().unwrap();
+            let brother_child = brother.children.pop();
+            brother.update();
+            let parent_data = std::mem::replace(&mut self.data[pos - 1], brother_data);
+            let child = self.child_mut(pos);
+            child.data.insert(0, parent_data);
+            if let Some(brother_child) = brother_child {
+                child.children.insert(0, brother_child);
+            }
+            child.update();
+            RotateDirection::Right
+        } else {
+            // 右の兄弟から値を持ってくる
+            // child.data = [*child.data, parent_data];
+            // child.children = [*child.children, brother.children.first];
+            let brother = self.child_mut(pos + 1);
+            let brother_data = brother.data.remove(0);
+            let brother_child = (!brother.is_leaf()).then(|| brother.children.remove(0));
+            brother.update();
+            let parent_data = std::mem::replace(&mut self.data[pos], brother_data);
+            let child = self.child_mut(pos);
+            child.data.push(parent_data);
+            child.children.extend(brother_child);
+            child.update();
+            RotateDirection::Left
+        }
+    }
+
+    /// 
+    /// make self.children[pos] not sparse by merging it with a sibling, and return the position of the merged child.
+    /// this should only be called when self.size() > 1 and all of its sibling are sparse.
+    /// 
+    fn merge(&mut self, pos: usize) -> usize {
+        // 兄弟要素の値と親要素の間の値を左側のノードにまとめる.
+        let left_pos = if pos < self.size() { pos } else { pos - 1 };
+        let parent_data = self.data.remove(left_pos);
+        let right = L::into_inner(self.children.remove(left_pos + 1));
+        let left = self.child_mut(left_pos);
+        left.data.push(parent_data);
+        left.data.extend(right.data);
+        left.children.extend(right.children);
+        left.update();
+        left_pos
+    }
+
+    /// 
+    /// make the tree 1 level lower by pulling up both of the children of self.
+    /// this should only be called when self.size() == 1 and both of its children are sparse.
+    /// 
+    fn shrink(&mut self) {
+        // self.data = [left.data, self.data, right.data];
+        // self.children = [*left.children, *right.children];
+        let right = L::into_inner(self.children.pop().unwrap());
+        let left = L::into_inner(self.children.pop().unwrap());
+        let mid = self.data.pop().unwrap();
+        self.data = left.data;
+        self.data.push(mid);
+        self.data.extend(right.data);
+        self.children = left.children;
+        self.children.extend(right.children);
+    }
+
+    pub(crate) fn child_mut(&mut self, pos: usize) -> &mut Self {
+        L::get_mut(&mut self.children[pos])
+    }
+}
+
+impl<'a, T, const B: usize, M: Monoid<T>, L: Link> IntoIterator for &'a BTree<T, B, M, L> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, B, M, L>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// 
+/// an iterator over the values of a Tree234 in ascending order.
+/// created by [`BTree::iter`].
 /// 
-/// remove array[index], shifting the rest of the array to the left.
-/// 
-fn delete_from_array<S>(array: &mut [S], index: usize) -> S {
-    #[cold]
-    #[cfg_attr(not(feature = "panic_immediate_abort"), inline(never))]
-    #[track_caller]
-    fn assert_failed(index: usize, len: usize) -> ! {
-        panic!("insertion index (is {index}) should be <= len (is {len})");
-    }
-    let len = 3;
-
-    if index > len {
-        assert_failed(index, len);
-    }
-
-    unsafe {
-        // infallible
-        // The spot to put the new value
-        let p = array.as_mut_ptr().add(index);
-        let ret = ptr::read(p);
-        // Shift everything over to make space. (Duplicating the
-        // `index`th element into two consecutive places.)
-        ptr::copy(p.add(1), p, len - index);
-        return ret;
+#[derive(Clone)]
+pub struct Iter<'a, T, const B: usize = 4, M: Monoid<T> = (), L: Link = Owned> {
+    /// 
+    /// nodes on the path to the next value and the index of the next value in each node.
+    /// 
+    stack: Vec<(&'a BTree<T, B, M, L>, usize)>,
+}
+
+impl<'a, T, const B: usize, M: Monoid<T>, L: Link> Iter<'a, T, B, M, L> {
+    fn push_leftmost(&mut self, mut node: &'a BTree<T, B, M, L>) {
+        loop {
+            self.stack.push((node, 0));
+            if node.is_leaf() {
+                break;
+            }
+            node = node.child(0);
+        }
+    }
+}
+
+impl<'a, T, const B: usize, M: Monoid<T>, L: Link> Iterator for Iter<'a, T, B, M, L> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let (node, pos) = self.stack.pop()?;
+            if pos < node.size() {
+                self.stack.push((node, pos + 1));
+                if !node.is_leaf() {
+                    self.push_leftmost(node.child(pos + 1));
+                }
+                return Some(node.key(pos));
+            }
+        }
+    }
+}
+
+/// 
+/// an iterator over a sub-range of the values of a Tree234.
+/// created by [`BTree::range`].
+/// 
+#[derive(Clone)]
+pub struct Range<'a, T, const B: usize = 4, M: Monoid<T> = (), L: Link = Owned> {
+    iter: Iter<'a, T, B, M, L>,
+    /// 
+    /// the last value to yield. None if the range is exhausted.
+    /// 
+    last: Option<&'a T>,
+}
+
+impl<'a, T, const B: usize, M: Monoid<T>, L: Link> Iterator for Range<'a, T, B, M, L> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let last = self.last?;
+        let value = self.iter.next()?;
+        if ptr::eq(value, last) {
+            self.last = None;
+        }
+        Some(value)
     }
 }
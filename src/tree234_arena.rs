@@ -0,0 +1,1080 @@
+use crate::{array_vec::ArrayVec, BTree, InvariantError};
+use std::{
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+    ptr,
+};
+
+///
+/// A node of an [`ArenaBTree`]. children are indices into the arena.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Node<T, const B: usize> {
+    pub(crate) data: ArrayVec<T, B>,
+    pub(crate) children: ArrayVec<usize, B>,
+    pub(crate) length: usize,
+}
+
+impl<T, const B: usize> Default for Node<T, B> {
+    fn default() -> Self {
+        Self {
+            data: ArrayVec::new(),
+            children: ArrayVec::new(),
+            length: 0,
+        }
+    }
+}
+
+impl<T, const B: usize> Node<T, B> {
+    fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.size() == B - 1
+    }
+
+    fn is_sparse(&self) -> bool {
+        self.size() < B / 2
+    }
+
+    ///
+    /// number of leading self.data which satisfy pred.
+    ///
+    fn partition(&self, pred: impl Fn(&T) -> bool) -> usize {
+        self.data.iter().take_while(|x| pred(x)).count()
+    }
+}
+
+///
+/// A [`BTree`] which keeps all of its nodes in one `Vec` and links them by index,
+/// so building a large tree allocates a few times instead of once per node.
+/// Nodes removed by deletion are reused by later insertions,
+/// and `clear` keeps the arena for the next values.
+/// It is a multiset in the same way as `BTree`, with its lookups, order statistics, insertions and deletions.
+///
+/// The algorithms of `BTree` reach each child through a [`Link`](crate::Link), which is a pointer on its own,
+/// while a child here is an index which means nothing without the arena,
+/// so the node operations are written again for the arena instead of being shared.
+/// To keep that copy small, it stops at the operations on one tree which need no other arena:
+/// for `split_off`, joins and set algebra, convert into a `BTree` and back, which takes O(n) each way.
+///
+/// # Example
+/// ```
+/// use algorithm::ArenaTree234;
+/// let mut tree = ArenaTree234::with_capacity(1000);
+/// for i in 0..1000 {
+///     tree.insert(i);
+/// }
+/// assert!(tree.find(&500));
+/// let capacity = tree.capacity();
+/// tree.clear();
+/// assert!(tree.is_empty());
+/// assert_eq!(tree.capacity(), capacity);
+/// tree.append((0..10).collect());
+/// let mut boxed = algorithm::Tree234::from(tree);
+/// let right = boxed.split_off(&5);
+/// assert_eq!(ArenaTree234::from(right).len(), 5);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct ArenaBTree<T, const B: usize = 4> {
+    ///
+    /// every node of the tree, including the released ones.
+    ///
+    pub(crate) nodes: Vec<Node<T, B>>,
+    ///
+    /// indices of the released nodes, which are reused before the arena grows.
+    ///
+    pub(crate) free: Vec<usize>,
+    ///
+    /// index of the root. None until the first value is inserted.
+    ///
+    pub(crate) root: Option<usize>,
+}
+
+///
+/// the arena-backed 2-3-4 tree.
+///
+pub type ArenaTree234<T> = ArenaBTree<T, 4>;
+
+impl<T: PartialEq, const B: usize> PartialEq for ArenaBTree<T, B> {
+    ///
+    /// two trees are equal if they have the same shape with the same values in each node, as for `BTree`,
+    /// wherever their nodes are in the arena.
+    ///
+    fn eq(&self, other: &Self) -> bool {
+        match (self.root, other.root) {
+            _ if self.is_empty() || other.is_empty() => self.is_empty() && other.is_empty(),
+            (Some(root), Some(other_root)) => self.same_shape(root, other, other_root),
+            _ => unreachable!("a non-empty tree has a root"),
+        }
+    }
+}
+
+impl<T: Eq, const B: usize> Eq for ArenaBTree<T, B> {}
+
+impl<T: Ord, const B: usize> From<Vec<T>> for ArenaBTree<T, B> {
+    ///
+    /// a method to make a tree from a Vec in O(n log n).
+    /// duplicated values are stored only once; use `from_sorted_iter` to keep them.
+    ///
+    fn from(mut v: Vec<T>) -> Self {
+        v.sort();
+        v.dedup();
+        let mut tree = Self::new();
+        tree.build_from_sorted(v);
+        tree
+    }
+}
+
+impl<T, const B: usize> From<ArenaBTree<T, B>> for BTree<T, B> {
+    ///
+    /// move the values into a boxed tree in O(n), for the operations which only [`BTree`] has.
+    /// duplicated values are kept.
+    ///
+    fn from(mut tree: ArenaBTree<T, B>) -> Self {
+        Self::from_sorted_vec(tree.take_sorted())
+    }
+}
+
+impl<T, const B: usize> From<BTree<T, B>> for ArenaBTree<T, B> {
+    ///
+    /// move the values of a boxed tree into one arena in O(n). duplicated values are kept.
+    ///
+    fn from(tree: BTree<T, B>) -> Self {
+        let values = tree.into_sorted_vec();
+        let mut arena = Self::with_capacity(values.len());
+        arena.build_from_sorted(values);
+        arena
+    }
+}
+
+impl<T, const B: usize> Default for ArenaBTree<T, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const B: usize> ArenaBTree<T, B> {
+    pub fn new() -> Self {
+        const { assert!(B >= 4 && B.is_multiple_of(2), "B should be an even number of at least 4") };
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+        }
+    }
+
+    ///
+    /// make an empty tree whose arena can hold capacity values without growing.
+    ///
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut tree = Self::new();
+        // 各ノードは値を1つ以上持つので, 値の数だけノードがあれば足りる.
+        tree.nodes.reserve(capacity);
+        tree
+    }
+
+    ///
+    /// return the number of nodes the arena can hold without growing.
+    ///
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    ///
+    /// clear the tree. the arena is kept for the next values.
+    ///
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = None;
+    }
+
+    ///
+    /// check if the tree is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// return the number of values in the tree in O(1).
+    ///
+    pub fn len(&self) -> usize {
+        self.root.map_or(0, |root| self.nodes[root].length)
+    }
+
+    ///
+    /// return the k-th smallest value (0-indexed) in O(log n).
+    ///
+    pub fn nth(&self, mut k: usize) -> Option<&T> {
+        if k >= self.len() {
+            return None;
+        }
+        let mut node = &self.nodes[self.root?];
+        'outer: loop {
+            if node.is_leaf() {
+                return Some(&node.data[k]);
+            }
+            for i in 0..=node.size() {
+                let child = &self.nodes[node.children[i]];
+                if k < child.length {
+                    node = child;
+                    continue 'outer;
+                }
+                k -= child.length;
+                if k == 0 {
+                    return Some(&node.data[i]);
+                }
+                k -= 1;
+            }
+            unreachable!("length of the node is inconsistent with its children");
+        }
+    }
+
+    ///
+    /// return the smallest value in the tree.
+    ///
+    pub fn first(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut node = &self.nodes[self.root?];
+        while !node.is_leaf() {
+            node = &self.nodes[node.children[0]];
+        }
+        Some(&node.data[0])
+    }
+
+    ///
+    /// return the largest value in the tree.
+    ///
+    pub fn last(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut node = &self.nodes[self.root?];
+        while !node.is_leaf() {
+            node = &self.nodes[node.children[node.size()]];
+        }
+        Some(&node.data[node.size() - 1])
+    }
+
+    ///
+    /// remove the smallest value from the tree and return it.
+    ///
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let root = self.root?;
+        Some(self.pop_first_at(root))
+    }
+
+    ///
+    /// remove the largest value from the tree and return it.
+    ///
+    pub fn pop_last(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let root = self.root?;
+        Some(self.pop_last_at(root))
+    }
+
+    ///
+    /// iterate over the values in ascending order.
+    ///
+    pub fn iter(&self) -> ArenaIter<'_, T, B> {
+        let mut iter = ArenaIter {
+            tree: self,
+            stack: vec![],
+        };
+        if let Some(root) = self.root {
+            iter.push_leftmost(root);
+        }
+        iter
+    }
+
+    ///
+    /// return the number of levels of the tree. an empty tree has height 0.
+    ///
+    pub fn height(&self) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut node = &self.nodes[self.root.unwrap()];
+        let mut height = 1;
+        while !node.is_leaf() {
+            node = &self.nodes[node.children[0]];
+            height += 1;
+        }
+        height
+    }
+
+    ///
+    /// put node into a released slot of the arena, or at its end, and return its index.
+    ///
+    fn alloc(&mut self, node: Node<T, B>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    ///
+    /// take the node at index out of the arena and mark the slot as reusable.
+    ///
+    fn release(&mut self, index: usize) -> Node<T, B> {
+        self.free.push(index);
+        std::mem::take(&mut self.nodes[index])
+    }
+
+    ///
+    /// return the index of the root, making an empty root if there is none.
+    ///
+    fn root_or_alloc(&mut self) -> usize {
+        match self.root {
+            Some(root) => root,
+            None => {
+                let root = self.alloc(Node::default());
+                self.root = Some(root);
+                root
+            }
+        }
+    }
+
+    fn child(&self, index: usize, pos: usize) -> usize {
+        self.nodes[index].children[pos]
+    }
+
+    ///
+    /// recompute the values cached in the node at index from its data and children.
+    ///
+    fn update(&mut self, index: usize) {
+        let node = &self.nodes[index];
+        let length = node.size() + node.children.iter().map(|&child| self.nodes[child].length).sum::<usize>();
+        self.nodes[index].length = length;
+    }
+
+    ///
+    /// make the tree from values which are already sorted in O(n), reusing the arena.
+    ///
+    fn build_from_sorted(&mut self, values: Vec<T>) {
+        self.clear();
+        let len = values.len();
+        // 高さhの木には最大でB^h - 1個の値が入るので, それに収まる最小の高さで作る.
+        let mut height = 0;
+        while B.saturating_pow(height as u32) - 1 < len {
+            height += 1;
+        }
+        self.root = Some(self.build(&mut values.into_iter(), len, height));
+    }
+
+    ///
+    /// build a subtree of exactly height levels from the next len values of values.
+    /// len must be between 2^height - 1 and B^height - 1.
+    ///
+    fn build(&mut self, values: &mut impl Iterator<Item = T>, len: usize, height: usize) -> usize {
+        let mut node = Node::default();
+        if height <= 1 {
+            node.data = values.take(len).collect();
+            node.length = len;
+            return self.alloc(node);
+        }
+        // 子の数をできるだけ少なくし, 値を子に均等に配る.
+        let capacity = B.saturating_pow(height as u32 - 1) - 1;
+        let width = (2..=B).find(|&c| len - (c - 1) <= c * capacity).unwrap();
+        let (base, rem) = ((len - (width - 1)) / width, (len - (width - 1)) % width);
+        for i in 0..width {
+            let child_len = base + usize::from(i < rem);
+            node.children.push(self.build(values, child_len, height - 1));
+            if i + 1 < width {
+                node.data.push(values.next().unwrap());
+            }
+        }
+        node.length = len;
+        self.alloc(node)
+    }
+
+    ///
+    /// take all values out of the tree in ascending order, keeping the arena.
+    ///
+    fn take_sorted(&mut self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len());
+        if let Some(root) = self.root {
+            self.drain_into(root, &mut values);
+        }
+        self.clear();
+        values
+    }
+
+    fn drain_into(&mut self, index: usize, values: &mut Vec<T>) {
+        let node = std::mem::take(&mut self.nodes[index]);
+        let mut children = node.children.into_iter();
+        for value in node.data {
+            if let Some(child) = children.next() {
+                self.drain_into(child, values);
+            }
+            values.push(value);
+        }
+        if let Some(child) = children.next() {
+            self.drain_into(child, values);
+        }
+    }
+
+    ///
+    /// split the root which is full, making the tree 1 level higher.
+    ///
+    fn split_root(&mut self) {
+        let root = self.root.unwrap();
+        self.root = Some(self.grow(root));
+    }
+
+    ///
+    /// split the node at index which is full under a new node, and return the index of the new node.
+    ///
+    fn grow(&mut self, index: usize) -> usize {
+        let length = self.nodes[index].length;
+        let (mid, right) = self.split_full(index);
+        let mut node = Node::default();
+        node.data.push(mid);
+        node.children.push(index);
+        node.children.push(right);
+        node.length = length;
+        self.alloc(node)
+    }
+
+    ///
+    /// split the child at pos of the node at index which is full, and move its middle value to the node.
+    /// this should only be called when the node is not full.
+    ///
+    fn split_child(&mut self, index: usize, pos: usize) {
+        let child = self.child(index, pos);
+        let (mid, right) = self.split_full(child);
+        // 重複した値がある場合も分割した子の位置に中央の値を入れる.
+        let node = &mut self.nodes[index];
+        node.data.insert(pos, mid);
+        node.children.insert(pos + 1, right);
+    }
+
+    ///
+    /// take apart the node at index which has B - 1 values into its middle value and two nodes.
+    /// the node at index keeps the left half, and the index of the right half is returned.
+    ///
+    fn split_full(&mut self, index: usize) -> (T, usize) {
+        let node = &mut self.nodes[index];
+        let mut right = Node {
+            data: node.data.split_off(B / 2),
+            ..Node::default()
+        };
+        if !node.is_leaf() {
+            right.children = node.children.split_off(B / 2);
+        }
+        let mid = node.data.pop().unwrap();
+        let right = self.alloc(right);
+        self.update(index);
+        self.update(right);
+        (mid, right)
+    }
+
+    ///
+    /// remove the smallest value of the subtree at index.
+    /// the subtree must not be empty, and its root must not be sparse unless it is the root of the tree.
+    ///
+    fn pop_first_at(&mut self, index: usize) -> T {
+        if self.nodes[index].is_leaf() {
+            self.nodes[index].length -= 1;
+            return self.nodes[index].data.remove(0);
+        }
+        if self.nodes[self.child(index, 0)].is_sparse() {
+            self.delete_balance(index, 0);
+            return self.pop_first_at(index);
+        }
+        self.nodes[index].length -= 1;
+        self.pop_first_at(self.child(index, 0))
+    }
+
+    ///
+    /// remove the largest value of the subtree at index.
+    /// the subtree must not be empty, and its root must not be sparse unless it is the root of the tree.
+    ///
+    fn pop_last_at(&mut self, index: usize) -> T {
+        if self.nodes[index].is_leaf() {
+            self.nodes[index].length -= 1;
+            return self.nodes[index].data.pop().unwrap();
+        }
+        let size = self.nodes[index].size();
+        if self.nodes[self.child(index, size)].is_sparse() {
+            self.delete_balance(index, size);
+            return self.pop_last_at(index);
+        }
+        self.nodes[index].length -= 1;
+        self.pop_last_at(self.child(index, size))
+    }
+
+    ///
+    /// make the child at pos of the node at index not sparse.
+    ///
+    fn delete_balance(&mut self, index: usize, pos: usize) {
+        let size = self.nodes[index].size();
+        // 隣接兄弟ノードに値の余裕がある場合, 回転を行う
+        if pos > 0 && !self.nodes[self.child(index, pos - 1)].is_sparse() {
+            self.rotate_right(index, pos);
+        } else if pos < size && !self.nodes[self.child(index, pos + 1)].is_sparse() {
+            self.rotate_left(index, pos);
+        } else if size > 1 {
+            // 隣接兄弟ノードに余裕がなく親要素の大きさが2以上の場合, マージを行う
+            self.merge(index, pos);
+        } else {
+            // 高さを1下げる.
+            self.shrink(index);
+        }
+    }
+
+    ///
+    /// move a value from the left sibling of the child at pos through the node at index.
+    ///
+    fn rotate_right(&mut self, index: usize, pos: usize) {
+        let brother = self.child(index, pos - 1);
+        let brother_data = self.nodes[brother].data.pop().unwrap();
+        let brother_child = self.nodes[brother].children.pop();
+        self.update(brother);
+        let parent_data = std::mem::replace(&mut self.nodes[index].data[pos - 1], brother_data);
+        let child = self.child(index, pos);
+        self.nodes[child].data.insert(0, parent_data);
+        if let Some(brother_child) = brother_child {
+            self.nodes[child].children.insert(0, brother_child);
+        }
+        self.update(child);
+    }
+
+    ///
+    /// move a value from the right sibling of the child at pos through the node at index.
+    ///
+    fn rotate_left(&mut self, index: usize, pos: usize) {
+        let brother = self.child(index, pos + 1);
+        let brother_data = self.nodes[brother].data.remove(0);
+        let brother_child = (!self.nodes[brother].is_leaf()).then(|| self.nodes[brother].children.remove(0));
+        self.update(brother);
+        let parent_data = std::mem::replace(&mut self.nodes[index].data[pos], brother_data);
+        let child = self.child(index, pos);
+        self.nodes[child].data.push(parent_data);
+        self.nodes[child].children.extend(brother_child);
+        self.update(child);
+    }
+
+    ///
+    /// merge the child at pos of the node at index with a sibling and the value between them.
+    /// this should only be called when the node has more than 1 value and the siblings are sparse.
+    ///
+    fn merge(&mut self, index: usize, pos: usize) {
+        // 兄弟要素の値と親要素の間の値を左側のノードにまとめる.
+        let left_pos = if pos < self.nodes[index].size() { pos } else { pos - 1 };
+        let parent_data = self.nodes[index].data.remove(left_pos);
+        let right = self.nodes[index].children.remove(left_pos + 1);
+        let right = self.release(right);
+        let left = self.child(index, left_pos);
+        self.nodes[left].data.push(parent_data);
+        self.nodes[left].data.extend(right.data);
+        self.nodes[left].children.extend(right.children);
+        self.update(left);
+    }
+
+    ///
+    /// pull both children of the node at index up into it, making the tree 1 level lower.
+    /// this should only be called when the node has 1 value and both of its children are sparse.
+    ///
+    fn shrink(&mut self, index: usize) {
+        let right = self.nodes[index].children.pop().unwrap();
+        let left = self.nodes[index].children.pop().unwrap();
+        let (left, right) = (self.release(left), self.release(right));
+        let node = &mut self.nodes[index];
+        let mid = node.data.pop().unwrap();
+        node.data = left.data;
+        node.data.push(mid);
+        node.data.extend(right.data);
+        node.children = left.children;
+        node.children.extend(right.children);
+    }
+
+    ///
+    /// check if the subtree at index has the same shape and values as the subtree of other at other_index.
+    ///
+    fn same_shape(&self, index: usize, other: &Self, other_index: usize) -> bool
+    where
+        T: PartialEq,
+    {
+        let (node, other_node) = (&self.nodes[index], &other.nodes[other_index]);
+        node.data.iter().eq(other_node.data.iter())
+            && node.children.len() == other_node.children.len()
+            && node.children.iter().zip(other_node.children.iter()).all(|(&a, &b)| self.same_shape(a, other, b))
+    }
+
+    ///
+    /// number of values in the tree which satisfy pred.
+    /// pred must hold for a prefix of the values in ascending order.
+    ///
+    fn count_while(&self, pred: impl Fn(&T) -> bool) -> usize {
+        let Some(mut index) = self.root else {
+            return 0;
+        };
+        let mut count = 0;
+        loop {
+            let node = &self.nodes[index];
+            let pos = node.partition(&pred);
+            count += pos;
+            if node.is_leaf() {
+                return count;
+            }
+            count += node.children.iter().take(pos).map(|&child| self.nodes[child].length).sum::<usize>();
+            index = node.children[pos];
+        }
+    }
+}
+
+impl<T: Ord, const B: usize> ArenaBTree<T, B> {
+    ///
+    /// append all value in values to self.
+    ///
+    pub fn append(&mut self, mut values: Vec<T>) {
+        if self.is_empty() {
+            values.sort();
+            self.build_from_sorted(values);
+            return;
+        }
+        for value in values {
+            self.insert(value);
+        }
+    }
+
+    ///
+    /// make a tree from values in ascending order in O(n).
+    /// duplicated values are kept.
+    /// # Panics
+    /// panics if values are not sorted.
+    ///
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let values: Vec<T> = values.into_iter().collect();
+        assert!(values.is_sorted(), "values should be sorted in ascending order");
+        let mut tree = Self::with_capacity(values.len());
+        tree.build_from_sorted(values);
+        tree
+    }
+
+    ///
+    /// insert value to self
+    ///
+    pub fn insert(&mut self, value: T) {
+        let root = self.root_or_alloc();
+        if self.nodes[root].is_full() {
+            self.split_root();
+        }
+        let mut index = self.root.unwrap();
+        loop {
+            // 降りる前に子を分割しておくので, 葉に着いたときに必ず空きがある.
+            self.nodes[index].length += 1;
+            let mut pos = self.find_index(index, &value);
+            if self.nodes[index].is_leaf() {
+                self.nodes[index].data.insert(pos, value);
+                return;
+            }
+            if self.nodes[self.child(index, pos)].is_full() {
+                self.split_child(index, pos);
+                if value > self.nodes[index].data[pos] {
+                    pos += 1;
+                }
+            }
+            index = self.child(index, pos);
+        }
+    }
+
+    ///
+    /// insert value to self only if self does not contain it yet.
+    /// return true if value was inserted.
+    ///
+    pub fn insert_unique(&mut self, value: T) -> bool {
+        if self.find(&value) {
+            return false;
+        }
+        self.insert(value);
+        true
+    }
+
+    ///
+    /// if value is in self, delete one occurrence of it and return true. else return false
+    ///
+    pub fn delete(&mut self, value: &T) -> bool {
+        match self.root {
+            Some(root) => self.delete_at(root, value),
+            None => false,
+        }
+    }
+
+    ///
+    /// same as `delete`: delete one occurrence of value and return true if there was one.
+    ///
+    pub fn delete_one(&mut self, value: &T) -> bool {
+        self.delete(value)
+    }
+
+    ///
+    /// delete every occurrence of value and return how many were deleted.
+    ///
+    pub fn delete_all(&mut self, value: &T) -> usize {
+        let mut deleted = 0;
+        while self.delete(value) {
+            deleted += 1;
+        }
+        deleted
+    }
+
+    ///
+    /// return the number of occurrences of value in O(log n).
+    ///
+    pub fn count(&self, value: &T) -> usize {
+        self.count_range(value..=value)
+    }
+
+    ///
+    /// check if the tree contains value.
+    ///
+    pub fn find(&self, value: &T) -> bool {
+        self.lower_bound(Bound::Included(value)) == Some(value)
+    }
+
+    ///
+    /// search the value and return (lower_bound, upper_bound)
+    /// lower_bound <= value <= upper_bound
+    ///
+    pub fn search_and_get_range(&self, value: &T) -> (Option<&T>, Option<&T>) {
+        (
+            self.upper_bound(Bound::Included(value)),
+            self.lower_bound(Bound::Included(value)),
+        )
+    }
+
+    ///
+    /// return the smallest value which lies above the bound.
+    ///
+    pub fn lower_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        let mut index = self.root?;
+        let mut found = None;
+        loop {
+            let node = &self.nodes[index];
+            let pos = index_after(node, bound);
+            if pos < node.size() {
+                found = Some(&node.data[pos]);
+            }
+            if node.is_leaf() {
+                return found;
+            }
+            index = node.children[pos];
+        }
+    }
+
+    ///
+    /// return the largest value which lies below the bound.
+    ///
+    pub fn upper_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        let mut index = self.root?;
+        let mut found = None;
+        loop {
+            let node = &self.nodes[index];
+            let pos = index_before(node, bound);
+            if pos > 0 {
+                found = Some(&node.data[pos - 1]);
+            }
+            if node.is_leaf() {
+                return found;
+            }
+            index = node.children[pos];
+        }
+    }
+
+    ///
+    /// return the largest value which is smaller than value.
+    ///
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        self.upper_bound(Bound::Excluded(value))
+    }
+
+    ///
+    /// return the smallest value which is larger than value.
+    ///
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        self.lower_bound(Bound::Excluded(value))
+    }
+
+    ///
+    /// return the number of values which are smaller than value in O(log n).
+    ///
+    pub fn rank(&self, value: &T) -> usize {
+        self.count_range(..value)
+    }
+
+    ///
+    /// return the number of values inside range in O(log n).
+    ///
+    pub fn count_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let before_start = match range.start_bound() {
+            Bound::Included(start) => self.count_while(|x| x < start),
+            Bound::Excluded(start) => self.count_while(|x| x <= start),
+            Bound::Unbounded => 0,
+        };
+        let before_end = match range.end_bound() {
+            Bound::Included(end) => self.count_while(|x| x <= end),
+            Bound::Excluded(end) => self.count_while(|x| x < end),
+            Bound::Unbounded => self.len(),
+        };
+        before_end.saturating_sub(before_start)
+    }
+
+    ///
+    /// iterate over the values inside range in ascending order.
+    ///
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> ArenaRange<'_, T, B> {
+        let first = self.lower_bound(range.start_bound());
+        let mut last = self.upper_bound(range.end_bound());
+        if matches!((first, last), (Some(first), Some(last)) if first > last) {
+            last = None;
+        }
+        let mut iter = ArenaIter {
+            tree: self,
+            stack: vec![],
+        };
+        let mut index = self.root;
+        while let Some(i) = index {
+            let node = &self.nodes[i];
+            let pos = index_after(node, range.start_bound());
+            iter.stack.push((i, pos));
+            index = (!node.is_leaf()).then(|| node.children[pos]);
+        }
+        ArenaRange { iter, last }
+    }
+
+    ///
+    /// check the structural invariants of the tree. see [`BTree::validate`](crate::BTree::validate).
+    ///
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let Some(root) = self.root else {
+            return Ok(());
+        };
+        let node = &self.nodes[root];
+        if node.size() == 0 && node.is_leaf() {
+            // 空の根だけは値を持たなくてよい.
+            return match node.length {
+                0 => Ok(()),
+                cached => Err(InvariantError::LengthMismatch { depth: 0, cached, actual: 0 }),
+            };
+        }
+        let mut leaf_depth = None;
+        self.validate_node(root, 0, None, None, &mut leaf_depth)?;
+        Ok(())
+    }
+
+    ///
+    /// check the subtree at index, whose values must lie between lower and upper.
+    /// return the number of values in the subtree.
+    ///
+    fn validate_node(
+        &self,
+        index: usize,
+        depth: usize,
+        lower: Option<&T>,
+        upper: Option<&T>,
+        leaf_depth: &mut Option<usize>,
+    ) -> Result<usize, InvariantError> {
+        let node = &self.nodes[index];
+        let size = node.size();
+        if size == 0 || size >= B {
+            return Err(InvariantError::NodeSize { depth, size });
+        }
+        if !node.is_leaf() && node.children.len() < size + 1 {
+            return Err(InvariantError::MissingChild { depth, index: node.children.len() });
+        }
+        if !node.is_leaf() && node.children.len() > size + 1 {
+            return Err(InvariantError::UnexpectedChild { depth, index: size + 1 });
+        }
+        for (i, value) in node.data.iter().enumerate() {
+            if i + 1 < size && value > &node.data[i + 1] {
+                return Err(InvariantError::UnsortedNode { depth, index: i });
+            }
+            if lower.is_some_and(|lower| lower > value) || upper.is_some_and(|upper| value > upper) {
+                return Err(InvariantError::OutOfRange { depth, index: i });
+            }
+        }
+        let mut actual = size;
+        if node.is_leaf() {
+            let expected = *leaf_depth.get_or_insert(depth);
+            if expected != depth {
+                return Err(InvariantError::UnevenDepth { expected, found: depth });
+            }
+        } else {
+            for (i, &child) in node.children.iter().enumerate() {
+                let lower = if i == 0 { lower } else { Some(&node.data[i - 1]) };
+                let upper = if i == size { upper } else { Some(&node.data[i]) };
+                actual += self.validate_node(child, depth + 1, lower, upper, leaf_depth)?;
+            }
+        }
+        if node.length != actual {
+            return Err(InvariantError::LengthMismatch { depth, cached: node.length, actual });
+        }
+        Ok(actual)
+    }
+
+    ///
+    /// delete one occurrence of value from the subtree at index.
+    ///
+    fn delete_at(&mut self, index: usize, value: &T) -> bool {
+        let pos = self.find_index(index, value);
+        let node = &self.nodes[index];
+        let found = pos < node.size() && &node.data[pos] == value;
+        if node.is_leaf() {
+            if found {
+                let node = &mut self.nodes[index];
+                node.data.remove(pos);
+                node.length -= 1;
+            }
+            return found;
+        }
+        if self.nodes[self.child(index, pos)].is_sparse() {
+            // 子ノードの値が少ない場合, 回転・マージ・縮小で増やしてから探しなおす.
+            self.delete_balance(index, pos);
+            return self.delete_at(index, value);
+        }
+        if found {
+            // 内部ノードである場合, 左の部分木の最大値で置き換える.
+            let predecessor = self.pop_last_at(self.child(index, pos));
+            let node = &mut self.nodes[index];
+            node.data[pos] = predecessor;
+            node.length -= 1;
+            return true;
+        }
+        let deleted = self.delete_at(self.child(index, pos), value);
+        if deleted {
+            self.nodes[index].length -= 1;
+        }
+        deleted
+    }
+
+    ///
+    /// the position in the node at index where value should be inserted.
+    ///
+    fn find_index(&self, index: usize, value: &T) -> usize {
+        self.nodes[index].partition(|x| x < value)
+    }
+}
+
+///
+/// number of node.data which lie before the bound as a lower bound.
+///
+fn index_after<T: Ord, const B: usize>(node: &Node<T, B>, bound: Bound<&T>) -> usize {
+    match bound {
+        Bound::Included(value) => node.partition(|x| x < value),
+        Bound::Excluded(value) => node.partition(|x| x <= value),
+        Bound::Unbounded => 0,
+    }
+}
+
+///
+/// number of node.data which lie inside the bound as an upper bound.
+///
+fn index_before<T: Ord, const B: usize>(node: &Node<T, B>, bound: Bound<&T>) -> usize {
+    match bound {
+        Bound::Included(value) => node.partition(|x| x <= value),
+        Bound::Excluded(value) => node.partition(|x| x < value),
+        Bound::Unbounded => node.size(),
+    }
+}
+
+impl<'a, T, const B: usize> IntoIterator for &'a ArenaBTree<T, B> {
+    type Item = &'a T;
+    type IntoIter = ArenaIter<'a, T, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+///
+/// an iterator over the values of an ArenaBTree in ascending order.
+/// created by [`ArenaBTree::iter`].
+///
+#[derive(Clone)]
+pub struct ArenaIter<'a, T, const B: usize = 4> {
+    tree: &'a ArenaBTree<T, B>,
+    ///
+    /// indices of the nodes on the path to the next value and the index of the next value in each node.
+    ///
+    stack: Vec<(usize, usize)>,
+}
+
+impl<'a, T, const B: usize> ArenaIter<'a, T, B> {
+    fn push_leftmost(&mut self, mut index: usize) {
+        loop {
+            self.stack.push((index, 0));
+            let node = &self.tree.nodes[index];
+            if node.is_leaf() {
+                break;
+            }
+            index = node.children[0];
+        }
+    }
+}
+
+impl<'a, T, const B: usize> Iterator for ArenaIter<'a, T, B> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let (index, pos) = self.stack.pop()?;
+            let node = &self.tree.nodes[index];
+            if pos < node.size() {
+                self.stack.push((index, pos + 1));
+                if !node.is_leaf() {
+                    self.push_leftmost(node.children[pos + 1]);
+                }
+                return Some(&node.data[pos]);
+            }
+        }
+    }
+}
+
+///
+/// an iterator over a sub-range of the values of an ArenaBTree.
+/// created by [`ArenaBTree::range`].
+///
+#[derive(Clone)]
+pub struct ArenaRange<'a, T, const B: usize = 4> {
+    iter: ArenaIter<'a, T, B>,
+    ///
+    /// the last value to yield. None if the range is exhausted.
+    ///
+    last: Option<&'a T>,
+}
+
+impl<'a, T, const B: usize> Iterator for ArenaRange<'a, T, B> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let last = self.last?;
+        let value = self.iter.next()?;
+        if ptr::eq(value, last) {
+            self.last = None;
+        }
+        Some(value)
+    }
+}
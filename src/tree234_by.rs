@@ -0,0 +1,338 @@
+use crate::{tree234_trace::Tracer, InvariantError, Iter, Range, Tree234};
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+};
+
+///
+/// a total order on T.
+/// every `Fn(&T, &T) -> Ordering` is a comparator.
+///
+pub trait Compare<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Compare<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+///
+/// a comparator which orders values by the key extracted from them.
+/// created by [`Tree234By::by_key`].
+///
+#[derive(Debug, Clone, Copy)]
+pub struct ByKey<G>(G);
+
+impl<T, K: Ord, G: Fn(&T) -> K> Compare<T> for ByKey<G> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).cmp(&(self.0)(b))
+    }
+}
+
+///
+/// A 2-3-4 tree which orders its values by a comparator instead of `Ord`.
+/// It has the same operations as [`Tree234`], and is a multiset in the same way.
+///
+/// # Example
+/// ```
+/// use algorithm::Tree234By;
+/// let mut tree = Tree234By::new(|a: &i32, b: &i32| b.cmp(a));
+/// tree.insert(1);
+/// tree.insert(3);
+/// tree.insert(2);
+/// assert_eq!(tree.iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+///
+/// let mut tree = Tree234By::by_key(|x: &(i32, &str)| x.0);
+/// tree.insert((2, "b"));
+/// tree.insert((1, "a"));
+/// assert_eq!(tree.first(), Some(&(1, "a")));
+/// ```
+///
+#[derive(Clone)]
+pub struct Tree234By<T, C> {
+    tree: Tree234<T>,
+    cmp: C,
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Tree234By<T, F> {
+    ///
+    /// make an empty tree ordered by cmp.
+    ///
+    pub fn new(cmp: F) -> Self {
+        Self {
+            tree: Tree234::new(),
+            cmp,
+        }
+    }
+}
+
+impl<T, K: Ord, G: Fn(&T) -> K> Tree234By<T, ByKey<G>> {
+    ///
+    /// make an empty tree ordered by the key extracted with key.
+    ///
+    pub fn by_key(key: G) -> Self {
+        Self {
+            tree: Tree234::new(),
+            cmp: ByKey(key),
+        }
+    }
+}
+
+impl<T, C> Tree234By<T, C> {
+    ///
+    /// clear the tree.
+    ///
+    pub fn clear(&mut self) {
+        self.tree.clear();
+    }
+
+    ///
+    /// check if the tree is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    ///
+    /// return the number of values in the tree in O(1).
+    ///
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    ///
+    /// return the k-th smallest value (0-indexed) in O(log n).
+    ///
+    pub fn nth(&self, k: usize) -> Option<&T> {
+        self.tree.nth(k)
+    }
+
+    ///
+    /// return the smallest value in the tree.
+    ///
+    pub fn first(&self) -> Option<&T> {
+        self.tree.first()
+    }
+
+    ///
+    /// return the largest value in the tree.
+    ///
+    pub fn last(&self) -> Option<&T> {
+        self.tree.last()
+    }
+
+    ///
+    /// iterate over the values in ascending order.
+    ///
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.tree.iter()
+    }
+}
+
+impl<T, C: Compare<T>> Tree234By<T, C> {
+    ///
+    /// check the structural invariants of the tree. see [`BTree::validate`].
+    ///
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        self.tree.validate_by(&self.cmp)
+    }
+
+    ///
+    /// remove the smallest value from the tree and return it.
+    ///
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.tree.pop_first_by(&self.cmp)
+    }
+
+    ///
+    /// remove the largest value from the tree and return it.
+    ///
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.tree.pop_last_by(&self.cmp)
+    }
+}
+
+impl<T, C: Compare<T>> Tree234By<T, C> {
+    ///
+    /// append all value in values to self, like [`BTree::append`](crate::BTree::append):
+    /// values are sorted with the comparator and built into a tree at once, which is joined to self
+    /// in O(k log k + log n) if no value comes before the values of self. otherwise they are inserted one by one in O(k log n).
+    ///
+    pub fn append(&mut self, values: Vec<T>) {
+        self.tree.append_by(values, &self.cmp);
+        self.tree.debug_validate(&self.cmp);
+    }
+
+    ///
+    /// insert value to self
+    ///
+    pub fn insert(&mut self, value: T) {
+        self.tree.insert_by(value, &self.cmp, &mut Tracer::off());
+        self.tree.debug_validate(&self.cmp);
+    }
+
+    ///
+    /// insert value to self only if self does not contain it yet.
+    /// return true if value was inserted.
+    ///
+    pub fn insert_unique(&mut self, value: T) -> bool {
+        if self.find(&value) {
+            return false;
+        }
+        self.insert(value);
+        true
+    }
+
+    ///
+    /// if value is in self, delete one occurrence of it and return true. else return false
+    ///
+    pub fn delete(&mut self, value: &T) -> bool {
+        let deleted = self.tree.delete_by(value, &self.cmp, &mut Tracer::off());
+        self.tree.debug_validate(&self.cmp);
+        deleted
+    }
+
+    ///
+    /// same as `delete`: delete one occurrence of value and return true if there was one.
+    ///
+    pub fn delete_one(&mut self, value: &T) -> bool {
+        self.delete(value)
+    }
+
+    ///
+    /// delete every occurrence of value and return how many were deleted in O(log n + m) for m occurrences.
+    ///
+    pub fn delete_all(&mut self, value: &T) -> usize {
+        let deleted = self.tree.delete_all_by(value, &self.cmp);
+        self.tree.debug_validate(&self.cmp);
+        deleted
+    }
+
+    ///
+    /// return the number of occurrences of value in O(log n).
+    ///
+    pub fn count(&self, value: &T) -> usize {
+        self.count_range(value..=value)
+    }
+
+    ///
+    /// check if the tree contains value.
+    ///
+    pub fn find(&self, value: &T) -> bool {
+        self.tree.find_by(value, &self.cmp)
+    }
+
+    ///
+    /// search the value and return (lower_bound, upper_bound)
+    /// lower_bound <= value <= upper_bound
+    ///
+    pub fn search_and_get_range(&self, value: &T) -> (Option<&T>, Option<&T>) {
+        (
+            self.upper_bound(Bound::Included(value)),
+            self.lower_bound(Bound::Included(value)),
+        )
+    }
+
+    ///
+    /// return the smallest value which lies above the bound.
+    ///
+    pub fn lower_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        self.tree.lower_bound_by(bound, &self.cmp)
+    }
+
+    ///
+    /// return the largest value which lies below the bound.
+    ///
+    pub fn upper_bound(&self, bound: Bound<&T>) -> Option<&T> {
+        self.tree.upper_bound_by(bound, &self.cmp)
+    }
+
+    ///
+    /// return the largest value which is smaller than value.
+    ///
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        self.upper_bound(Bound::Excluded(value))
+    }
+
+    ///
+    /// return the smallest value which is larger than value.
+    ///
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        self.lower_bound(Bound::Excluded(value))
+    }
+
+    ///
+    /// return the number of values which are smaller than value in O(log n).
+    ///
+    pub fn rank(&self, value: &T) -> usize {
+        self.count_range(..value)
+    }
+
+    ///
+    /// return the number of values inside range in O(log n).
+    ///
+    pub fn count_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        self.tree.count_range_by(range, &self.cmp)
+    }
+
+    ///
+    /// iterate over the values inside range in ascending order.
+    ///
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        self.tree.range_by(range, &self.cmp)
+    }
+
+    ///
+    /// move all values of other after the values of self in O(log n).
+    /// # Panics
+    /// panics if a value of self is larger than a value of other.
+    ///
+    pub fn append_tree(&mut self, other: Self) {
+        self.tree.append_tree_by(other.tree, &self.cmp);
+        self.tree.debug_validate(&self.cmp);
+    }
+
+    ///
+    /// concatenate left and right in O(log n). the comparator of left is kept.
+    /// # Panics
+    /// panics if a value of left is larger than a value of right.
+    ///
+    pub fn join(mut left: Self, right: Self) -> Self {
+        left.append_tree(right);
+        left
+    }
+}
+
+impl<T, C: Compare<T> + Clone> Tree234By<T, C> {
+    ///
+    /// split the tree at value in O(log n).
+    /// self keeps the values smaller than value, and the rest are returned.
+    ///
+    pub fn split_off(&mut self, value: &T) -> Self {
+        let tree = self.tree.split_off_by(value, &self.cmp);
+        self.tree.debug_validate(&self.cmp);
+        tree.debug_validate(&self.cmp);
+        Self {
+            tree,
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
+impl<T: Debug, C> Debug for Tree234By<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a Tree234By<T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,605 @@
+use crate::{BTree, Monoid};
+use std::ops::Bound;
+
+///
+/// A cursor over a [`BTree`] which stands at a value or at the "ghost" position after the last value.
+/// Moving past the last value reaches the ghost, and moving on from the ghost wraps around to the first value,
+/// like the cursors of `LinkedList`.
+/// The cursor keeps the path from the root to its value, so moving to a neighbour takes amortized O(1).
+///
+/// # Example
+/// ```
+/// use algorithm::Tree234;
+/// let tree = Tree234::from(vec![1, 2, 3]);
+/// let mut cursor = tree.cursor_front();
+/// assert_eq!(cursor.peek(), Some(&1));
+/// cursor.move_next();
+/// cursor.move_next();
+/// assert_eq!(cursor.peek(), Some(&3));
+/// cursor.move_next();
+/// assert_eq!(cursor.peek(), None);
+/// cursor.move_prev();
+/// assert_eq!(cursor.index(), Some(2));
+/// ```
+///
+pub struct Cursor<'a, T, const B: usize = 4, M: Monoid<T> = ()> {
+    tree: &'a BTree<T, B, M>,
+    ///
+    /// the path to the current value. the last entry holds the index of the value in its node,
+    /// and the others hold the index of the child the path goes down to. empty at the ghost.
+    ///
+    stack: Vec<(&'a BTree<T, B, M>, usize)>,
+    ///
+    /// index of the current value. tree.len() at the ghost.
+    ///
+    index: usize,
+}
+
+impl<T, const B: usize, M: Monoid<T>> Clone for Cursor<'_, T, B, M> {
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree,
+            stack: self.stack.clone(),
+            index: self.index,
+        }
+    }
+}
+
+impl<'a, T, const B: usize, M: Monoid<T>> Cursor<'a, T, B, M> {
+    ///
+    /// return the current value, or None at the ghost.
+    ///
+    pub fn peek(&self) -> Option<&'a T> {
+        let &(node, pos) = self.stack.last()?;
+        Some(node.key(pos))
+    }
+
+    ///
+    /// return the value after the current one without moving.
+    ///
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let mut cursor = self.clone();
+        cursor.move_next();
+        cursor.peek()
+    }
+
+    ///
+    /// return the value before the current one without moving.
+    ///
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let mut cursor = self.clone();
+        cursor.move_prev();
+        cursor.peek()
+    }
+
+    ///
+    /// return the index of the current value, or None at the ghost.
+    ///
+    pub fn index(&self) -> Option<usize> {
+        (!self.stack.is_empty()).then_some(self.index)
+    }
+
+    ///
+    /// move to the next value. from the last value this moves to the ghost, and from the ghost to the first value.
+    ///
+    pub fn move_next(&mut self) {
+        let Some(&(node, pos)) = self.stack.last() else {
+            *self = Self::front(self.tree);
+            return;
+        };
+        self.index += 1;
+        if !node.is_leaf() {
+            // 右の部分木の最小値へ降りる.
+            self.stack.last_mut().unwrap().1 = pos + 1;
+            self.push_leftmost(node.child(pos + 1));
+            return;
+        }
+        if pos + 1 < node.size() {
+            self.stack.last_mut().unwrap().1 = pos + 1;
+            return;
+        }
+        // 葉の末尾なので, まだ値が残っている祖先まで上る.
+        self.stack.pop();
+        while let Some(&(node, child)) = self.stack.last() {
+            if child < node.size() {
+                return;
+            }
+            self.stack.pop();
+        }
+    }
+
+    ///
+    /// move to the previous value. from the first value this moves to the ghost, and from the ghost to the last value.
+    ///
+    pub fn move_prev(&mut self) {
+        let Some(&(node, pos)) = self.stack.last() else {
+            *self = Self::back(self.tree);
+            return;
+        };
+        if !node.is_leaf() {
+            // 左の部分木の最大値へ降りる.
+            self.index -= 1;
+            self.push_rightmost(node.child(pos));
+            return;
+        }
+        if pos > 0 {
+            self.index -= 1;
+            self.stack.last_mut().unwrap().1 = pos - 1;
+            return;
+        }
+        self.stack.pop();
+        while let Some(&(_, child)) = self.stack.last() {
+            if child > 0 {
+                self.index -= 1;
+                self.stack.last_mut().unwrap().1 = child - 1;
+                return;
+            }
+            self.stack.pop();
+        }
+        self.index = self.tree.len();
+    }
+
+    pub(crate) fn front(tree: &'a BTree<T, B, M>) -> Self {
+        let mut cursor = Self {
+            tree,
+            stack: vec![],
+            index: 0,
+        };
+        if !tree.is_empty() {
+            cursor.push_leftmost(tree);
+        }
+        cursor
+    }
+
+    pub(crate) fn back(tree: &'a BTree<T, B, M>) -> Self {
+        let mut cursor = Self {
+            tree,
+            stack: vec![],
+            index: tree.len().saturating_sub(1),
+        };
+        if tree.is_empty() {
+            return cursor;
+        }
+        cursor.push_rightmost(tree);
+        cursor
+    }
+
+    fn push_leftmost(&mut self, mut node: &'a BTree<T, B, M>) {
+        loop {
+            self.stack.push((node, 0));
+            if node.is_leaf() {
+                break;
+            }
+            node = node.child(0);
+        }
+    }
+
+    fn push_rightmost(&mut self, mut node: &'a BTree<T, B, M>) {
+        loop {
+            if node.is_leaf() {
+                self.stack.push((node, node.size() - 1));
+                break;
+            }
+            self.stack.push((node, node.size()));
+            node = node.child(node.size());
+        }
+    }
+}
+
+impl<'a, T: Ord, const B: usize, M: Monoid<T>> Cursor<'a, T, B, M> {
+    pub(crate) fn lower_bound(tree: &'a BTree<T, B, M>, bound: Bound<&T>) -> Self {
+        let mut cursor = Self {
+            tree,
+            stack: vec![],
+            index: 0,
+        };
+        if tree.is_empty() {
+            return cursor;
+        }
+        let mut node = tree;
+        loop {
+            let pos = node.index_after(bound, &T::cmp);
+            cursor.stack.push((node, pos));
+            cursor.index += pos;
+            if node.is_leaf() {
+                break;
+            }
+            cursor.index += (0..pos).map(|i| node.child(i).length).sum::<usize>();
+            node = node.child(pos);
+        }
+        // 葉の末尾を指している場合は, 値の残っている祖先まで上る.
+        while let Some(&(node, pos)) = cursor.stack.last() {
+            if pos < node.size() {
+                break;
+            }
+            cursor.stack.pop();
+        }
+        cursor
+    }
+}
+
+///
+/// A cursor over a [`BTree`] which can also remove and insert values where it stands.
+/// It has the same positions and moves as [`Cursor`], but it cannot hold references into the tree it changes,
+/// so it takes the nodes on the path to its value out of their parents and keeps them on a stack instead.
+/// The node of the current value is then the top of the stack: a move inside a node only steps the position,
+/// and a move across nodes takes out or puts back one node per level, so moving to a neighbour takes amortized O(1).
+/// `remove_current`, `insert_before` and `insert_after` put the nodes back, rebalance the tree,
+/// and then find the path again from the index of the current value through the cached subtree lengths.
+/// The nodes are put back when the cursor is dropped, so the tree should not be used until then,
+/// and a cursor given to `mem::forget` leaves the tree without them.
+///
+/// # Example
+/// ```
+/// use algorithm::Tree234;
+/// let mut tree = Tree234::from(vec![1, 3, 5]);
+/// let mut cursor = tree.cursor_front_mut();
+/// cursor.move_next();
+/// assert_eq!(cursor.remove_current(), Some(3));
+/// assert_eq!(cursor.peek(), Some(&5));
+/// cursor.insert_before(4);
+/// cursor.insert_after(6);
+/// drop(cursor);
+/// assert_eq!(tree.iter().collect::<Vec<_>>(), vec![&1, &4, &5, &6]);
+/// ```
+///
+pub struct CursorMut<'a, T, const B: usize = 4, M: Monoid<T> = ()> {
+    tree: &'a mut BTree<T, B, M>,
+    ///
+    /// the nodes below the root on the path to the current value, from the top.
+    /// each one is taken out of the previous one, or of the root for the first, with the position it had there.
+    ///
+    pub(crate) path: Vec<(usize, Box<BTree<T, B, M>>)>,
+    ///
+    /// position of the current value in the last node of path, or in the root if path is empty.
+    ///
+    pos: usize,
+    ///
+    /// index of the current value. tree.len() at the ghost, where path is empty.
+    ///
+    index: usize,
+}
+
+impl<T, const B: usize, M: Monoid<T>> Drop for CursorMut<'_, T, B, M> {
+    fn drop(&mut self) {
+        self.zip();
+    }
+}
+
+impl<'a, T, const B: usize, M: Monoid<T>> CursorMut<'a, T, B, M> {
+    pub(crate) fn new(tree: &'a mut BTree<T, B, M>, index: usize) -> Self {
+        let mut cursor = Self { tree, path: vec![], pos: 0, index };
+        cursor.seek(index);
+        cursor
+    }
+
+    ///
+    /// return the current value, or None at the ghost.
+    ///
+    pub fn peek(&self) -> Option<&T> {
+        self.index()?;
+        Some(self.node().key(self.pos))
+    }
+
+    ///
+    /// return the value after the current one without moving.
+    ///
+    pub fn peek_next(&self) -> Option<&T> {
+        if self.index().is_none() {
+            return self.tree.first();
+        }
+        let node = self.node();
+        if !node.is_leaf() {
+            let mut child = node.child(self.pos + 1);
+            while !child.is_leaf() {
+                child = child.child(0);
+            }
+            return Some(child.key(0));
+        }
+        if self.pos + 1 < node.size() {
+            return Some(node.key(self.pos + 1));
+        }
+        self.ancestors().find_map(|(parent, pos)| (pos < parent.size()).then(|| parent.key(pos)))
+    }
+
+    ///
+    /// return the value before the current one without moving.
+    ///
+    pub fn peek_prev(&self) -> Option<&T> {
+        if self.index().is_none() {
+            return self.tree.last();
+        }
+        let node = self.node();
+        if !node.is_leaf() {
+            let mut child = node.child(self.pos);
+            while !child.is_leaf() {
+                child = child.child(child.size());
+            }
+            return Some(child.key(child.size() - 1));
+        }
+        if self.pos > 0 {
+            return Some(node.key(self.pos - 1));
+        }
+        self.ancestors().find_map(|(parent, pos)| (pos > 0).then(|| parent.key(pos - 1)))
+    }
+
+    ///
+    /// return the index of the current value, or None at the ghost.
+    ///
+    pub fn index(&self) -> Option<usize> {
+        (self.index < self.tree.len()).then_some(self.index)
+    }
+
+    ///
+    /// move to the next value. from the last value this moves to the ghost, and from the ghost to the first value.
+    ///
+    pub fn move_next(&mut self) {
+        if self.index().is_none() {
+            self.index = 0;
+            if !self.tree.is_empty() {
+                self.leftmost();
+            }
+            return;
+        }
+        self.index += 1;
+        if !self.node().is_leaf() {
+            // 右の部分木の最小値へ降りる.
+            self.descend(self.pos + 1);
+            self.leftmost();
+            return;
+        }
+        if self.pos + 1 < self.node().size() {
+            self.pos += 1;
+            return;
+        }
+        // 葉の末尾なので, まだ値が残っている祖先まで上る. 根まで上れば幽霊の位置になる.
+        while !self.path.is_empty() {
+            let pos = self.climb();
+            if pos < self.node().size() {
+                self.pos = pos;
+                return;
+            }
+        }
+    }
+
+    ///
+    /// move to the previous value. from the first value this moves to the ghost, and from the ghost to the last value.
+    ///
+    pub fn move_prev(&mut self) {
+        if self.index().is_none() {
+            if !self.tree.is_empty() {
+                self.index = self.tree.len() - 1;
+                self.rightmost();
+            }
+            return;
+        }
+        if !self.node().is_leaf() {
+            // 左の部分木の最大値へ降りる.
+            self.index -= 1;
+            self.descend(self.pos);
+            self.rightmost();
+            return;
+        }
+        if self.pos > 0 {
+            self.index -= 1;
+            self.pos -= 1;
+            return;
+        }
+        while !self.path.is_empty() {
+            let pos = self.climb();
+            if pos > 0 {
+                self.index -= 1;
+                self.pos = pos - 1;
+                return;
+            }
+        }
+        self.index = self.tree.len();
+    }
+
+    ///
+    /// the node of the current value.
+    ///
+    fn node(&self) -> &BTree<T, B, M> {
+        self.path.last().map_or(&*self.tree, |(_, node)| node)
+    }
+
+    fn node_mut(&mut self) -> &mut BTree<T, B, M> {
+        match self.path.last_mut() {
+            Some((_, node)) => node,
+            None => self.tree,
+        }
+    }
+
+    ///
+    /// the nodes above the current one from the bottom, each with the position of the child on the path below it.
+    ///
+    fn ancestors(&self) -> impl Iterator<Item = (&BTree<T, B, M>, usize)> {
+        (0..self.path.len()).rev().map(|i| {
+            let parent = if i == 0 { &*self.tree } else { &*self.path[i - 1].1 };
+            (parent, self.path[i].0)
+        })
+    }
+
+    ///
+    /// take the child at pos out of the current node and make it the current node.
+    ///
+    fn descend(&mut self, pos: usize) {
+        let child = self.node_mut().children.remove(pos);
+        self.path.push((pos, child));
+    }
+
+    ///
+    /// put the current node back into its parent and make the parent the current node.
+    /// return the position of the child in the parent.
+    ///
+    fn climb(&mut self) -> usize {
+        let (pos, child) = self.path.pop().unwrap();
+        self.node_mut().children.insert(pos, child);
+        pos
+    }
+
+    ///
+    /// put every node on the path back, so that the tree is whole again.
+    ///
+    fn zip(&mut self) {
+        while !self.path.is_empty() {
+            self.climb();
+        }
+    }
+
+    fn leftmost(&mut self) {
+        while !self.node().is_leaf() {
+            self.descend(0);
+        }
+        self.pos = 0;
+    }
+
+    fn rightmost(&mut self) {
+        while !self.node().is_leaf() {
+            self.descend(self.node().size());
+        }
+        self.pos = self.node().size() - 1;
+    }
+
+    ///
+    /// move to the index-th value, or to the ghost if index >= tree.len(), finding the path again.
+    /// the path is found through the cached subtree lengths without comparing values.
+    ///
+    fn seek(&mut self, index: usize) {
+        self.zip();
+        self.index = index.min(self.tree.len());
+        if index >= self.tree.len() {
+            return;
+        }
+        let mut k = index;
+        loop {
+            let node = self.node();
+            if node.is_leaf() {
+                self.pos = k;
+                return;
+            }
+            let mut pos = 0;
+            while k > node.child(pos).length {
+                k -= node.child(pos).length + 1;
+                pos += 1;
+            }
+            if k == node.child(pos).length {
+                self.pos = pos;
+                return;
+            }
+            self.descend(pos);
+        }
+    }
+}
+
+impl<T: Ord, const B: usize, M: Monoid<T>> CursorMut<'_, T, B, M> {
+    ///
+    /// remove the current value and move to the next one. return None at the ghost.
+    ///
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.index()?;
+        self.zip();
+        let value = self.tree.remove_at(self.index);
+        self.tree.debug_validate(&T::cmp);
+        self.seek(self.index);
+        Some(value)
+    }
+
+    ///
+    /// insert value just before the current value, or at the end at the ghost.
+    /// the cursor stays at the current value.
+    /// # Panics
+    /// panics if value does not lie between the values before and after the position.
+    ///
+    pub fn insert_before(&mut self, value: T) {
+        Self::check_between(self.peek_prev(), self.peek(), &value);
+        self.zip();
+        self.tree.insert_at(self.index, value);
+        self.tree.debug_validate(&T::cmp);
+        self.seek(self.index + 1);
+    }
+
+    ///
+    /// insert value just after the current value, or at the front at the ghost.
+    /// the cursor stays at the current value.
+    /// # Panics
+    /// panics if value does not lie between the values before and after the position.
+    ///
+    pub fn insert_after(&mut self, value: T) {
+        Self::check_between(self.peek(), self.peek_next(), &value);
+        self.zip();
+        // 幽霊の位置では先頭に挿入し, 幽霊の位置に留まる.
+        let (at, index) = match self.index() {
+            Some(index) => (index + 1, index),
+            None => (0, self.tree.len() + 1),
+        };
+        self.tree.insert_at(at, value);
+        self.tree.debug_validate(&T::cmp);
+        self.seek(index);
+    }
+
+    fn check_between(prev: Option<&T>, next: Option<&T>, value: &T) {
+        let sorted = prev.is_none_or(|prev| prev <= value) && next.is_none_or(|next| value <= next);
+        assert!(sorted, "the inserted value should lie between its neighbours");
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// return a cursor at the smallest value, or at the ghost if the tree is empty.
+    ///
+    pub fn cursor_front(&self) -> Cursor<'_, T, B, M> {
+        Cursor::front(self)
+    }
+
+    ///
+    /// return a cursor at the largest value, or at the ghost if the tree is empty.
+    ///
+    pub fn cursor_back(&self) -> Cursor<'_, T, B, M> {
+        Cursor::back(self)
+    }
+
+    ///
+    /// return a mutable cursor at the smallest value, or at the ghost if the tree is empty.
+    ///
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, B, M> {
+        CursorMut::new(self, 0)
+    }
+
+    ///
+    /// return a mutable cursor at the largest value, or at the ghost if the tree is empty.
+    ///
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, B, M> {
+        let index = self.len().saturating_sub(1);
+        CursorMut::new(self, index)
+    }
+}
+
+impl<T: Ord, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// return a cursor at the smallest value which lies above the bound, or at the ghost if there is none.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// use std::ops::Bound;
+    /// let tree = Tree234::from(vec![10, 20, 30]);
+    /// let cursor = tree.cursor_lower_bound(Bound::Excluded(&10));
+    /// assert_eq!(cursor.peek(), Some(&20));
+    /// assert_eq!(cursor.peek_prev(), Some(&10));
+    /// ```
+    ///
+    pub fn cursor_lower_bound(&self, bound: Bound<&T>) -> Cursor<'_, T, B, M> {
+        Cursor::lower_bound(self, bound)
+    }
+
+    ///
+    /// return a mutable cursor at the smallest value which lies above the bound, or at the ghost if there is none.
+    ///
+    pub fn cursor_lower_bound_mut(&mut self, bound: Bound<&T>) -> CursorMut<'_, T, B, M> {
+        let index = match bound {
+            Bound::Included(value) => self.count_while(&|x| x < value),
+            Bound::Excluded(value) => self.count_while(&|x| x <= value),
+            Bound::Unbounded => 0,
+        };
+        CursorMut::new(self, index)
+    }
+}
@@ -0,0 +1,90 @@
+use std::{ops::Deref, rc::Rc};
+
+///
+/// how a [`BTree`](crate::BTree) node holds its children.
+/// [`Owned`] boxes each child, and [`Shared`] counts references to it,
+/// so that copies of a tree share the nodes they have not changed.
+/// the algorithms of `BTree` are written once for both.
+///
+pub trait Link {
+    type Ptr<N>: Deref<Target = N>;
+
+    fn new<N>(node: N) -> Self::Ptr<N>;
+
+    ///
+    /// copy a child. `Owned` copies the whole subtree, and `Shared` only the pointer.
+    ///
+    fn clone_ptr<N: Clone>(ptr: &Self::Ptr<N>) -> Self::Ptr<N>;
+}
+
+///
+/// a [`Link`] whose children of type N can be changed.
+///
+pub trait LinkMut<N>: Link {
+    ///
+    /// return the child for writing. `Shared` copies it first if another tree shares it.
+    ///
+    fn get_mut(ptr: &mut Self::Ptr<N>) -> &mut N;
+
+    ///
+    /// take the child out of its pointer. `Shared` copies it if another tree shares it.
+    ///
+    fn into_inner(ptr: Self::Ptr<N>) -> N;
+}
+
+///
+/// children owned by their parent through `Box`. this is the default of [`BTree`](crate::BTree).
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Owned;
+
+///
+/// children shared through `Rc` between copies of a tree, and copied on write.
+/// this is the link of [`PersistentBTree`](crate::PersistentBTree).
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Shared;
+
+impl Link for Owned {
+    type Ptr<N> = Box<N>;
+
+    fn new<N>(node: N) -> Box<N> {
+        Box::new(node)
+    }
+
+    fn clone_ptr<N: Clone>(ptr: &Box<N>) -> Box<N> {
+        ptr.clone()
+    }
+}
+
+impl<N> LinkMut<N> for Owned {
+    fn get_mut(ptr: &mut Box<N>) -> &mut N {
+        ptr
+    }
+
+    fn into_inner(ptr: Box<N>) -> N {
+        *ptr
+    }
+}
+
+impl Link for Shared {
+    type Ptr<N> = Rc<N>;
+
+    fn new<N>(node: N) -> Rc<N> {
+        Rc::new(node)
+    }
+
+    fn clone_ptr<N: Clone>(ptr: &Rc<N>) -> Rc<N> {
+        Rc::clone(ptr)
+    }
+}
+
+impl<N: Clone> LinkMut<N> for Shared {
+    fn get_mut(ptr: &mut Rc<N>) -> &mut N {
+        Rc::make_mut(ptr)
+    }
+
+    fn into_inner(ptr: Rc<N>) -> N {
+        Rc::unwrap_or_clone(ptr)
+    }
+}
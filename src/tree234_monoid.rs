@@ -0,0 +1,284 @@
+use crate::BTree;
+use std::ops::{Add, Bound, RangeBounds};
+
+///
+/// an associative operation with an identity, whose product over every subtree is cached in a [`BTree`].
+/// op must be associative and identity must be its identity, but op need not be commutative:
+/// the values are always combined in ascending order.
+///
+/// # Example
+/// ```
+/// use algorithm::{BTree, Monoid};
+/// // the number of values and their sum, to take averages of ranges.
+/// struct CountSum;
+/// impl Monoid<i64> for CountSum {
+///     type Value = (usize, i64);
+///     fn identity() -> (usize, i64) {
+///         (0, 0)
+///     }
+///     fn single(value: &i64) -> (usize, i64) {
+///         (1, *value)
+///     }
+///     fn op(a: &(usize, i64), b: &(usize, i64)) -> (usize, i64) {
+///         (a.0 + b.0, a.1 + b.1)
+///     }
+/// }
+/// let tree = BTree::<i64, 4, CountSum>::from(vec![1, 2, 3, 4, 5, 6]);
+/// assert_eq!(tree.fold(2..=4), (3, 9));
+/// ```
+///
+pub trait Monoid<T> {
+    type Value: Clone;
+    fn identity() -> Self::Value;
+    fn single(value: &T) -> Self::Value;
+    fn op(a: &Self::Value, b: &Self::Value) -> Self::Value;
+}
+
+///
+/// the monoid which caches nothing. the default of [`BTree`].
+///
+impl<T> Monoid<T> for () {
+    type Value = ();
+    fn identity() {}
+    fn single(_: &T) {}
+    fn op(_: &(), _: &()) {}
+}
+
+///
+/// the sum of the values. the identity is `T::default()`.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sum;
+
+impl<T: Clone + Default + Add<Output = T>> Monoid<T> for Sum {
+    type Value = T;
+    fn identity() -> T {
+        T::default()
+    }
+    fn single(value: &T) -> T {
+        value.clone()
+    }
+    fn op(a: &T, b: &T) -> T {
+        a.clone() + b.clone()
+    }
+}
+
+///
+/// the largest value, or None for no value.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max;
+
+impl<T: Clone + Ord> Monoid<T> for Max {
+    type Value = Option<T>;
+    fn identity() -> Option<T> {
+        None
+    }
+    fn single(value: &T) -> Option<T> {
+        Some(value.clone())
+    }
+    fn op(a: &Option<T>, b: &Option<T>) -> Option<T> {
+        // Noneは任意のSomeより小さい.
+        a.clone().max(b.clone())
+    }
+}
+
+///
+/// the smallest value, or None for no value.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min;
+
+impl<T: Clone + Ord> Monoid<T> for Min {
+    type Value = Option<T>;
+    fn identity() -> Option<T> {
+        None
+    }
+    fn single(value: &T) -> Option<T> {
+        Some(value.clone())
+    }
+    fn op(a: &Option<T>, b: &Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b).clone()),
+            _ => a.clone().or_else(|| b.clone()),
+        }
+    }
+}
+
+impl<T: Ord, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// return the product of M over the values inside range in ascending order in O(B log n).
+    /// ```rust
+    /// use algorithm::{BTree, Sum};
+    /// let tree = BTree::<i32, 4, Sum>::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.fold(2..4), 5);
+    /// assert_eq!(tree.fold(..), 15);
+    /// assert_eq!(tree.fold(10..), 0);
+    /// ```
+    ///
+    pub fn fold<R: RangeBounds<T>>(&self, range: R) -> M::Value {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.count_while(&|x| x < start),
+            Bound::Excluded(start) => self.count_while(&|x| x <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.count_while(&|x| x <= end),
+            Bound::Excluded(end) => self.count_while(&|x| x < end),
+            Bound::Unbounded => self.length,
+        };
+        self.fold_index(start, end)
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// return the largest r such that pred holds for the product of the l-th to (r - 1)-th smallest values,
+    /// by binary search on the cached products in O(B log n).
+    /// pred must hold for the identity, and once it fails for some r it must fail for every larger r.
+    /// # Panics
+    /// panics if l > self.len() or pred does not hold for the identity.
+    /// ```rust
+    /// use algorithm::{BTree, Sum};
+    /// let tree = BTree::<i32, 4, Sum>::from(vec![1, 2, 3, 4, 5]);
+    /// // 1 + 2 + 3 <= 6 < 1 + 2 + 3 + 4
+    /// assert_eq!(tree.max_right(0, |&sum| sum <= 6), 3);
+    /// assert_eq!(tree.max_right(3, |&sum| sum <= 100), 5);
+    /// ```
+    ///
+    pub fn max_right(&self, l: usize, pred: impl Fn(&M::Value) -> bool) -> usize {
+        assert!(l <= self.length, "l (is {l}) should be <= len (is {})", self.length);
+        let mut acc = M::identity();
+        assert!(pred(&acc), "pred should hold for the identity");
+        self.max_right_at(l, &pred, &mut acc).unwrap_or(self.length)
+    }
+
+    ///
+    /// return the smallest l such that pred holds for the product of the l-th to (r - 1)-th smallest values,
+    /// by binary search on the cached products in O(B log n).
+    /// pred must hold for the identity, and once it fails for some l it must fail for every smaller l.
+    /// # Panics
+    /// panics if r > self.len() or pred does not hold for the identity.
+    /// ```rust
+    /// use algorithm::{BTree, Sum};
+    /// let tree = BTree::<i32, 4, Sum>::from(vec![1, 2, 3, 4, 5]);
+    /// // 4 + 5 <= 9 < 3 + 4 + 5
+    /// assert_eq!(tree.min_left(5, |&sum| sum <= 9), 3);
+    /// ```
+    ///
+    pub fn min_left(&self, r: usize, pred: impl Fn(&M::Value) -> bool) -> usize {
+        assert!(r <= self.length, "r (is {r}) should be <= len (is {})", self.length);
+        let mut acc = M::identity();
+        assert!(pred(&acc), "pred should hold for the identity");
+        self.min_left_at(r, &pred, &mut acc).unwrap_or(0)
+    }
+
+    ///
+    /// product of M over the l-th to (r - 1)-th smallest values of the subtree.
+    ///
+    fn fold_index(&self, l: usize, r: usize) -> M::Value {
+        if l >= r {
+            return M::identity();
+        }
+        if l == 0 && r == self.length {
+            return self.aggregate.clone();
+        }
+        // 範囲に完全に含まれる子はキャッシュした積を使い, 境界の子だけ再帰する.
+        let mut acc = M::identity();
+        let mut start = 0;
+        for i in 0..=self.size() {
+            if !self.is_leaf() {
+                let child = self.child(i);
+                let end = start + child.length;
+                if l < end && start < r {
+                    acc = M::op(&acc, &child.fold_index(l.saturating_sub(start), r.min(end) - start));
+                }
+                start = end;
+            }
+            if i < self.size() {
+                if l <= start && start < r {
+                    acc = M::op(&acc, &M::single(self.key(i)));
+                }
+                start += 1;
+            }
+        }
+        acc
+    }
+
+    ///
+    /// extend acc to the right from the l-th value of the subtree while pred holds.
+    /// return the index where pred fails, or None if it holds up to the end of the subtree.
+    ///
+    fn max_right_at(&self, l: usize, pred: &impl Fn(&M::Value) -> bool, acc: &mut M::Value) -> Option<usize> {
+        if l == 0 {
+            let next = M::op(acc, &self.aggregate);
+            if pred(&next) {
+                *acc = next;
+                return None;
+            }
+        }
+        let mut start = 0;
+        for i in 0..=self.size() {
+            if !self.is_leaf() {
+                let child = self.child(i);
+                let end = start + child.length;
+                if l < end {
+                    if let Some(r) = child.max_right_at(l.saturating_sub(start), pred, acc) {
+                        return Some(start + r);
+                    }
+                }
+                start = end;
+            }
+            if i < self.size() {
+                if l <= start {
+                    let next = M::op(acc, &M::single(self.key(i)));
+                    if !pred(&next) {
+                        return Some(start);
+                    }
+                    *acc = next;
+                }
+                start += 1;
+            }
+        }
+        None
+    }
+
+    ///
+    /// extend acc to the left from the (r - 1)-th value of the subtree while pred holds.
+    /// return the index where pred fails, or None if it holds down to the start of the subtree.
+    ///
+    fn min_left_at(&self, r: usize, pred: &impl Fn(&M::Value) -> bool, acc: &mut M::Value) -> Option<usize> {
+        if r == self.length {
+            let next = M::op(&self.aggregate, acc);
+            if pred(&next) {
+                *acc = next;
+                return None;
+            }
+        }
+        let mut end = self.length;
+        for i in (0..=self.size()).rev() {
+            if !self.is_leaf() {
+                let child = self.child(i);
+                let start = end - child.length;
+                if start < r {
+                    if let Some(l) = child.min_left_at(r.min(end) - start, pred, acc) {
+                        return Some(start + l);
+                    }
+                }
+                end = start;
+            }
+            if i > 0 {
+                // self.data[i - 1]はend - 1番目の値.
+                if end <= r {
+                    let next = M::op(&M::single(self.key(i - 1)), acc);
+                    if !pred(&next) {
+                        return Some(end);
+                    }
+                    *acc = next;
+                }
+                end -= 1;
+            }
+        }
+        None
+    }
+}
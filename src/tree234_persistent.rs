@@ -0,0 +1,276 @@
+use crate::{BTree, InvariantError, Iter, Shared};
+use std::{fmt::Debug, rc::Rc};
+
+///
+/// A node of a [`PersistentBTree`]: a [`BTree`] whose children may be shared with other versions of the tree.
+///
+pub(crate) type PersistentNode<T, const B: usize> = BTree<T, B, (), Shared>;
+
+///
+/// A persistent [`BTree`] whose nodes are shared by `Rc` between versions.
+/// `clone` takes O(1), and insertion and deletion copy only the nodes on the path they walk,
+/// so a modified version shares all the other nodes with the versions it was cloned from,
+/// which are never changed.
+/// It runs the algorithms of `BTree` on nodes linked by [`Shared`], and is a multiset in the same way.
+///
+/// # Example
+/// ```
+/// use algorithm::PersistentTree234;
+/// let empty = PersistentTree234::new();
+/// let one = empty.with_inserted(1);
+/// let mut two = one.clone();
+/// two.insert(2);
+/// assert_eq!(empty.len(), 0);
+/// assert_eq!(one.iter().collect::<Vec<_>>(), vec![&1]);
+/// assert_eq!(two.iter().collect::<Vec<_>>(), vec![&1, &2]);
+/// ```
+///
+pub struct PersistentBTree<T, const B: usize = 4> {
+    pub(crate) root: Rc<PersistentNode<T, B>>,
+}
+
+impl<T, const B: usize> Clone for PersistentBTree<T, B> {
+    ///
+    /// make another version sharing every node with self in O(1).
+    ///
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+        }
+    }
+}
+
+///
+/// the persistent 2-3-4 tree.
+///
+pub type PersistentTree234<T> = PersistentBTree<T, 4>;
+
+///
+/// an iterator over the values of a PersistentBTree in ascending order.
+/// created by [`PersistentBTree::iter`].
+///
+pub type PersistentIter<'a, T, const B: usize = 4> = Iter<'a, T, B, (), Shared>;
+
+impl<T: PartialEq, const B: usize> PartialEq for PersistentBTree<T, B> {
+    ///
+    /// two versions are equal if their trees have the same shape and values, as for `BTree`.
+    ///
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+impl<T: Eq, const B: usize> Eq for PersistentBTree<T, B> {}
+
+impl<T: Ord, const B: usize> From<Vec<T>> for PersistentBTree<T, B> {
+    ///
+    /// make a tree from a Vec in O(n log n), building it in O(n) after sorting like `BTree::from`.
+    /// duplicated values are stored only once.
+    ///
+    fn from(v: Vec<T>) -> Self {
+        Self {
+            root: Rc::new(BTree::from(v)),
+        }
+    }
+}
+
+impl<T, const B: usize> Default for PersistentBTree<T, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug, const B: usize> Debug for PersistentBTree<T, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const B: usize> PersistentBTree<T, B> {
+    pub fn new() -> Self {
+        Self {
+            root: Rc::new(BTree::new()),
+        }
+    }
+
+    ///
+    /// clear the tree. other versions keep their values.
+    ///
+    pub fn clear(&mut self) {
+        self.root = Rc::new(BTree::new());
+    }
+
+    ///
+    /// check if the tree is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+
+    ///
+    /// return the number of values in the tree in O(1).
+    ///
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    ///
+    /// check if self and other are the same version, sharing the whole tree.
+    ///
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.root, &other.root)
+    }
+
+    ///
+    /// return the k-th smallest value (0-indexed) in O(log n).
+    ///
+    pub fn nth(&self, k: usize) -> Option<&T> {
+        self.root.nth(k)
+    }
+
+    ///
+    /// return the smallest value in the tree.
+    ///
+    pub fn first(&self) -> Option<&T> {
+        self.root.first()
+    }
+
+    ///
+    /// return the largest value in the tree.
+    ///
+    pub fn last(&self) -> Option<&T> {
+        self.root.last()
+    }
+
+    ///
+    /// iterate over the values in ascending order.
+    ///
+    pub fn iter(&self) -> PersistentIter<'_, T, B> {
+        self.root.iter()
+    }
+
+    ///
+    /// return the number of levels of the tree. an empty tree has height 0.
+    ///
+    pub fn height(&self) -> usize {
+        self.root.height()
+    }
+}
+
+impl<T: Ord + Clone, const B: usize> PersistentBTree<T, B> {
+    ///
+    /// insert value to self. the nodes shared with other versions are copied before they are changed.
+    /// ```rust
+    /// use algorithm::PersistentTree234;
+    /// let mut tree = PersistentTree234::new();
+    /// tree.insert(1);
+    /// let old = tree.clone();
+    /// tree.insert(2);
+    /// assert!(!old.find(&2));
+    /// ```
+    ///
+    pub fn insert(&mut self, value: T) {
+        Rc::make_mut(&mut self.root).insert(value);
+    }
+
+    ///
+    /// return a new version of self with value inserted, leaving self unchanged.
+    ///
+    pub fn with_inserted(&self, value: T) -> Self {
+        let mut tree = self.clone();
+        tree.insert(value);
+        tree
+    }
+
+    ///
+    /// insert value to self only if self does not contain it yet.
+    /// return true if value was inserted.
+    ///
+    pub fn insert_unique(&mut self, value: T) -> bool {
+        if self.find(&value) {
+            return false;
+        }
+        self.insert(value);
+        true
+    }
+
+    ///
+    /// if value is in self, delete one occurrence of it and return true. else return false
+    /// ```rust
+    /// use algorithm::PersistentTree234;
+    /// let mut tree = PersistentTree234::from(vec![1, 2, 3]);
+    /// let old = tree.clone();
+    /// assert!(tree.delete(&2));
+    /// assert!(!tree.find(&2));
+    /// assert!(old.find(&2));
+    /// ```
+    ///
+    pub fn delete(&mut self, value: &T) -> bool {
+        if !self.find(value) {
+            // 見つからない場合は経路を複製しない.
+            return false;
+        }
+        Rc::make_mut(&mut self.root).delete(value)
+    }
+
+    ///
+    /// return a new version of self with one occurrence of value deleted, leaving self unchanged.
+    ///
+    pub fn with_deleted(&self, value: &T) -> Self {
+        let mut tree = self.clone();
+        tree.delete(value);
+        tree
+    }
+
+    ///
+    /// remove the smallest value from the tree and return it.
+    ///
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        Rc::make_mut(&mut self.root).pop_first()
+    }
+
+    ///
+    /// remove the largest value from the tree and return it.
+    ///
+    pub fn pop_last(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        Rc::make_mut(&mut self.root).pop_last()
+    }
+}
+
+impl<T: Ord, const B: usize> PersistentBTree<T, B> {
+    ///
+    /// check if the tree contains value.
+    ///
+    pub fn find(&self, value: &T) -> bool {
+        self.root.find_by(value, &T::cmp)
+    }
+
+    ///
+    /// return the number of values which are smaller than value in O(log n).
+    ///
+    pub fn rank(&self, value: &T) -> usize {
+        self.root.count_while(&|x| x < value)
+    }
+
+    ///
+    /// check the structural invariants of the tree. see [`BTree::validate`].
+    ///
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        self.root.validate()
+    }
+}
+
+impl<'a, T, const B: usize> IntoIterator for &'a PersistentBTree<T, B> {
+    type Item = &'a T;
+    type IntoIter = PersistentIter<'a, T, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
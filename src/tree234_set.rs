@@ -0,0 +1,240 @@
+use crate::{BTree, Iter, Monoid};
+use std::{cmp::Ordering, iter::Peekable};
+
+///
+/// set algebra between two trees.
+/// duplicates are treated as a multiset: a value appearing a times in self and b times in other
+/// appears max(a, b) times in the union, min(a, b) times in the intersection,
+/// a - b times in the difference and |a - b| times in the symmetric difference.
+///
+impl<T: Ord, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// iterate over the values of self or other in ascending order.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let a = Tree234::from(vec![1, 2, 3]);
+    /// let b = Tree234::from(vec![2, 4]);
+    /// assert_eq!(a.union(&b).collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+    /// ```
+    ///
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, B, M> {
+        Union(MergeIter::new(self.iter(), other.iter()))
+    }
+
+    ///
+    /// iterate over the values of both self and other in ascending order.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let a = Tree234::from(vec![1, 2, 3]);
+    /// let b = Tree234::from(vec![2, 3, 4]);
+    /// assert_eq!(a.intersection(&b).collect::<Vec<_>>(), vec![&2, &3]);
+    /// ```
+    ///
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T, B, M> {
+        Intersection(MergeIter::new(self.iter(), other.iter()))
+    }
+
+    ///
+    /// iterate over the values of self which are not in other in ascending order.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let a = Tree234::from(vec![1, 2, 3]);
+    /// let b = Tree234::from(vec![2, 4]);
+    /// assert_eq!(a.difference(&b).collect::<Vec<_>>(), vec![&1, &3]);
+    /// ```
+    ///
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T, B, M> {
+        Difference(MergeIter::new(self.iter(), other.iter()))
+    }
+
+    ///
+    /// iterate over the values of exactly one of self and other in ascending order.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let a = Tree234::from(vec![1, 2, 3]);
+    /// let b = Tree234::from(vec![2, 4]);
+    /// assert_eq!(a.symmetric_difference(&b).collect::<Vec<_>>(), vec![&1, &3, &4]);
+    /// ```
+    ///
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T, B, M> {
+        SymmetricDifference(MergeIter::new(self.iter(), other.iter()))
+    }
+
+    ///
+    /// check if every value of self is in other. O(n + m).
+    ///
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+
+    ///
+    /// check if every value of other is in self. O(n + m).
+    ///
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    ///
+    /// check if self and other have no value in common. O(n + m).
+    ///
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    ///
+    /// make self the union of self and other.
+    /// the trees are cut and joined at the boundaries where the values of self and other interleave,
+    /// so this takes O(k log n) for k interleaving runs instead of O(m log n) for inserting every value.
+    /// a value in both trees counts as a run of its own: its occurrences are counted in O(log n)
+    /// and the smaller group is cut out with `delete_all` in O(log n) plus its size.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let mut a = Tree234::from((0..100).collect::<Vec<_>>());
+    /// a.union_with(Tree234::from((50..200).collect::<Vec<_>>()));
+    /// assert!(a.iter().copied().eq(0..200));
+    /// ```
+    ///
+    pub fn union_with(&mut self, other: Self) {
+        let mut result = Self::new();
+        let mut left = std::mem::take(self);
+        let mut right = other;
+        loop {
+            let (first, other_first) = match (left.first(), right.first()) {
+                (None, _) => {
+                    result.append_tree_unchecked(right);
+                    break;
+                }
+                (_, None) => {
+                    result.append_tree_unchecked(left);
+                    break;
+                }
+                (Some(first), Some(other_first)) => (first, other_first),
+            };
+            match first.cmp(other_first) {
+                Ordering::Greater => std::mem::swap(&mut left, &mut right),
+                Ordering::Equal => {
+                    // 同じ値は多い方だけを残す.
+                    if left.count(first) < right.count(other_first) {
+                        left.delete_all(other_first);
+                    } else {
+                        right.delete_all(first);
+                    }
+                }
+                Ordering::Less => {
+                    // leftのうちrightの先頭より小さい部分をまとめて結果に移す.
+                    let rest = left.split_off(other_first);
+                    result.append_tree_unchecked(std::mem::replace(&mut left, rest));
+                }
+            }
+        }
+        *self = result;
+        self.debug_validate(&T::cmp);
+    }
+}
+
+///
+/// walks two ascending iterators at once, yielding the next smallest value of each side,
+/// or of both sides when they are equal.
+///
+pub(crate) struct MergeIter<I: Iterator> {
+    left: Peekable<I>,
+    right: Peekable<I>,
+}
+
+impl<I: Iterator> MergeIter<I> {
+    pub(crate) fn new(left: I, right: I) -> Self {
+        Self {
+            left: left.peekable(),
+            right: right.peekable(),
+        }
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Iterator<Item = &'a T>> Iterator for MergeIter<I> {
+    type Item = (Option<&'a T>, Option<&'a T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ordering = match (self.left.peek(), self.right.peek()) {
+            (None, None) => return None,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(left), Some(right)) => left.cmp(right),
+        };
+        Some(match ordering {
+            Ordering::Less => (self.left.next(), None),
+            Ordering::Greater => (None, self.right.next()),
+            Ordering::Equal => (self.left.next(), self.right.next()),
+        })
+    }
+}
+
+///
+/// an iterator over the union of two trees. created by [`BTree::union`].
+///
+pub struct Union<'a, T, const B: usize = 4, M: Monoid<T> = ()>(MergeIter<Iter<'a, T, B, M>>);
+
+impl<'a, T: Ord, const B: usize, M: Monoid<T>> Iterator for Union<'a, T, B, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let (left, right) = self.0.next()?;
+        left.or(right)
+    }
+}
+
+///
+/// an iterator over the intersection of two trees. created by [`BTree::intersection`].
+///
+pub struct Intersection<'a, T, const B: usize = 4, M: Monoid<T> = ()>(MergeIter<Iter<'a, T, B, M>>);
+
+impl<'a, T: Ord, const B: usize, M: Monoid<T>> Iterator for Intersection<'a, T, B, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            self.0.left.peek()?;
+            self.0.right.peek()?;
+            if let (Some(left), Some(_)) = self.0.next()? {
+                return Some(left);
+            }
+        }
+    }
+}
+
+///
+/// an iterator over the difference of two trees. created by [`BTree::difference`].
+///
+pub struct Difference<'a, T, const B: usize = 4, M: Monoid<T> = ()>(MergeIter<Iter<'a, T, B, M>>);
+
+impl<'a, T: Ord, const B: usize, M: Monoid<T>> Iterator for Difference<'a, T, B, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            self.0.left.peek()?;
+            if let (Some(left), None) = self.0.next()? {
+                return Some(left);
+            }
+        }
+    }
+}
+
+///
+/// an iterator over the symmetric difference of two trees.
+/// created by [`BTree::symmetric_difference`].
+///
+pub struct SymmetricDifference<'a, T, const B: usize = 4, M: Monoid<T> = ()>(MergeIter<Iter<'a, T, B, M>>);
+
+impl<'a, T: Ord, const B: usize, M: Monoid<T>> Iterator for SymmetricDifference<'a, T, B, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match self.0.next()? {
+                (Some(left), None) => return Some(left),
+                (None, Some(right)) => return Some(right),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{array_vec::ArrayVec, BTree, InvariantError, Monoid};
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+///
+/// an error from parsing the shape of a BTree with [`str::parse`].
+/// pos is the byte offset in the input.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeError {
+    /// a character which cannot come at pos.
+    UnexpectedChar { pos: usize, found: char },
+    /// the input ended inside a node, or had no node at all.
+    UnexpectedEnd,
+    /// the token at pos could not be parsed as a value.
+    InvalidValue { pos: usize, token: String },
+    /// the node opened at pos is nested deeper than any tree can be.
+    TooDeep { pos: usize },
+    /// the shape was read but breaks an invariant of the tree.
+    Invariant(InvariantError),
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeError::UnexpectedChar { pos, found } => write!(f, "unexpected {found:?} at {pos}"),
+            ShapeError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ShapeError::InvalidValue { pos, token } => write!(f, "{token:?} at {pos} is not a valid value"),
+            ShapeError::TooDeep { pos } => write!(f, "the node at {pos} is nested deeper than {MAX_DEPTH} levels"),
+            ShapeError::Invariant(error) => write!(f, "invalid tree: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+impl From<InvariantError> for ShapeError {
+    fn from(error: InvariantError) -> Self {
+        ShapeError::Invariant(error)
+    }
+}
+
+impl<T: Display, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// write the exact shape of the tree on one line, which [`str::parse`] reads back into the same tree.
+    /// a node is written as its values between brackets, with each child in its place between them,
+    /// so that `[[1] 2 [3 4]]` is a root holding 2 over the leaves `[1]` and `[3 4]`. an empty tree is `[]`.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(tree.to_shape(), "[[1 2 3] 4 [5 6]]");
+    /// assert_eq!("[[1 2 3] 4 [5 6]]".parse::<Tree234<i32>>(), Ok(tree));
+    /// ```
+    ///
+    pub fn to_shape(&self) -> String {
+        let mut shape = String::new();
+        self.write_shape(&mut shape);
+        shape
+    }
+
+    fn write_shape(&self, shape: &mut String) {
+        shape.push('[');
+        for i in 0..self.size() {
+            if !self.is_leaf() {
+                self.child(i).write_shape(shape);
+                shape.push(' ');
+            }
+            write!(shape, "{}", self.key(i)).unwrap();
+            if i + 1 < self.size() || !self.is_leaf() {
+                shape.push(' ');
+            }
+        }
+        if !self.is_leaf() {
+            self.child(self.size()).write_shape(shape);
+        }
+        shape.push(']');
+    }
+}
+
+impl<T: FromStr + Ord, const B: usize, M: Monoid<T>> FromStr for BTree<T, B, M> {
+    type Err = ShapeError;
+
+    ///
+    /// read a tree written by [`BTree::to_shape`], keeping the nodes exactly as written.
+    /// whitespace may be added around brackets and values.
+    /// the tree is validated, so a shape which breaks an invariant gives [`ShapeError::Invariant`].
+    ///
+    fn from_str(s: &str) -> Result<Self, ShapeError> {
+        let mut parser = ShapeParser { input: s, pos: 0 };
+        let tree = parser.node(0)?;
+        if let Some(found) = parser.peek() {
+            return Err(ShapeError::UnexpectedChar { pos: parser.pos, found });
+        }
+        tree.validate()?;
+        Ok(tree)
+    }
+}
+
+///
+/// the deepest level a node can be at. a tree of height h holds at least 2^h - 1 values,
+/// so no tree whose length fits in usize is deeper, and the parser stops here instead of overflowing the stack.
+///
+const MAX_DEPTH: usize = usize::BITS as usize;
+
+struct ShapeParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl ShapeParser<'_> {
+    ///
+    /// skip whitespace and return the next character without consuming it.
+    ///
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.pos..];
+        let trimmed = rest.trim_start();
+        self.pos += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ShapeError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(found) => Err(ShapeError::UnexpectedChar { pos: self.pos, found }),
+            None => Err(ShapeError::UnexpectedEnd),
+        }
+    }
+
+    ///
+    /// read a value token, which runs up to the next whitespace or bracket.
+    ///
+    fn value<T: FromStr>(&mut self) -> Result<T, ShapeError> {
+        if let Some(found @ ('[' | ']')) = self.peek() {
+            return Err(ShapeError::UnexpectedChar { pos: self.pos, found });
+        }
+        let start = self.pos;
+        let rest = &self.input[start..];
+        let end = rest.find(|c: char| c.is_whitespace() || c == '[' || c == ']').unwrap_or(rest.len());
+        self.pos += end;
+        let token = &rest[..end];
+        token.parse().map_err(|_| ShapeError::InvalidValue { pos: start, token: token.to_string() })
+    }
+
+    ///
+    /// read a node at depth. a node whose first item is a child alternates children and values,
+    /// and ends with a child. any other node is a leaf of values only.
+    ///
+    fn node<T: FromStr, const B: usize, M: Monoid<T>>(&mut self, depth: usize) -> Result<BTree<T, B, M>, ShapeError> {
+        if depth >= MAX_DEPTH {
+            return Err(ShapeError::TooDeep { pos: self.pos });
+        }
+        self.expect('[')?;
+        let mut data = vec![];
+        let mut children = vec![];
+        if self.peek() == Some('[') {
+            children.push(Box::new(self.node(depth + 1)?));
+            while self.peek().is_some_and(|c| c != ']') {
+                data.push(self.value()?);
+                // an internal node has a child after every value.
+                children.push(Box::new(self.node(depth + 1)?));
+            }
+        } else {
+            while self.peek().is_some_and(|c| c != ']') {
+                data.push(self.value()?);
+            }
+        }
+        self.expect(']')?;
+        if data.len() >= B {
+            // this does not fit in the ArrayVec, so reject it here before the validation.
+            return Err(ShapeError::Invariant(InvariantError::NodeSize { depth, size: data.len() }));
+        }
+        let mut node = BTree::new();
+        node.data = ArrayVec::from_iter(data);
+        node.children = ArrayVec::from_iter(children);
+        node.update();
+        Ok(node)
+    }
+}
@@ -0,0 +1,367 @@
+use crate::{BTree, Link, Monoid, Owned};
+use std::fmt::{Display, Write};
+
+///
+/// the way a value moves in a rotation, seen from the parent.
+/// `Right` moves the last value of the left sibling through the parent into the child,
+/// and `Left` moves the first value of the right sibling through the parent into the child.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotateDirection {
+    Left,
+    Right,
+}
+
+///
+/// one internal step of an insertion or a deletion recorded by [`BTree::insert_traced`] and [`BTree::delete_traced`].
+/// path is the list of child indices from the root to the node the step changed, in the tree after the step.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// a full node was split and its middle value moved up into the node at path.
+    /// a split root is reported with the new root, whose path is empty.
+    Split { path: Vec<usize> },
+    /// the child pos of the node at path borrowed a value from a sibling through the node.
+    Rotate { path: Vec<usize>, pos: usize, direction: RotateDirection },
+    /// the children pos and pos + 1 of the node at path were merged with the value between them.
+    Merge { path: Vec<usize>, pos: usize },
+    /// the node at path, which had 1 value, pulled up its two children, making the tree 1 level lower.
+    Shrink { path: Vec<usize> },
+    /// the value pos of the node at path was deleted and replaced by its predecessor.
+    SwapWithPredecessor { path: Vec<usize>, pos: usize },
+    /// a value was inserted at pos of the leaf at path.
+    Insert { path: Vec<usize>, pos: usize },
+    /// the value at pos of the leaf at path was removed.
+    Remove { path: Vec<usize>, pos: usize },
+}
+
+impl Display for TraceEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceEvent::Split { path } => write!(f, "split below {path:?}"),
+            TraceEvent::Rotate { path, pos, direction: RotateDirection::Left } => {
+                write!(f, "rotate left into child {pos} of {path:?}")
+            }
+            TraceEvent::Rotate { path, pos, direction: RotateDirection::Right } => {
+                write!(f, "rotate right into child {pos} of {path:?}")
+            }
+            TraceEvent::Merge { path, pos } => write!(f, "merge children {pos} and {} of {path:?}", pos + 1),
+            TraceEvent::Shrink { path } => write!(f, "shrink {path:?}"),
+            TraceEvent::SwapWithPredecessor { path, pos } => {
+                write!(f, "swap value {pos} of {path:?} with its predecessor")
+            }
+            TraceEvent::Insert { path, pos } => write!(f, "insert at {pos} of leaf {path:?}"),
+            TraceEvent::Remove { path, pos } => write!(f, "remove {pos} of leaf {path:?}"),
+        }
+    }
+}
+
+///
+/// a receiver of the steps of [`BTree::insert_traced`] and [`BTree::delete_traced`].
+/// node is the node at the path of the event right after the step, with its subtree.
+/// the nodes above it may not have updated their lengths and aggregates yet.
+///
+pub trait TraceHook<T, const B: usize, M: Monoid<T>, L: Link = Owned> {
+    ///
+    /// called once before the steps of each operation, with a label such as `insert 5` and the whole tree.
+    ///
+    fn begin(&mut self, _operation: &str, _tree: &BTree<T, B, M, L>) {}
+    fn step(&mut self, event: &TraceEvent, node: &BTree<T, B, M, L>);
+}
+
+///
+/// the hook given to the insertion and the deletion of [`BTree`], and the path from the root to the node they are at.
+/// the untraced operations pass `Tracer::off()`, which keeps no path and builds no event.
+///
+pub(crate) struct Tracer<'h, T, const B: usize, M: Monoid<T>, L: Link> {
+    hook: Option<&'h mut dyn TraceHook<T, B, M, L>>,
+    path: Vec<usize>,
+}
+
+impl<'h, T, const B: usize, M: Monoid<T>, L: Link> Tracer<'h, T, B, M, L> {
+    pub(crate) fn off() -> Self {
+        Self { hook: None, path: vec![] }
+    }
+
+    pub(crate) fn on(hook: &'h mut dyn TraceHook<T, B, M, L>) -> Self {
+        Self { hook: Some(hook), path: vec![] }
+    }
+
+    ///
+    /// go down to the child pos of the current node.
+    ///
+    pub(crate) fn enter(&mut self, pos: usize) {
+        if self.hook.is_some() {
+            self.path.push(pos);
+        }
+    }
+
+    ///
+    /// go back up to the parent of the current node.
+    ///
+    pub(crate) fn leave(&mut self) {
+        self.path.pop();
+    }
+
+    ///
+    /// report the step which has just changed node, the current node, to the hook if there is one.
+    ///
+    pub(crate) fn step(&mut self, node: &BTree<T, B, M, L>, event: impl FnOnce(Vec<usize>) -> TraceEvent) {
+        if let Some(hook) = self.hook.as_deref_mut() {
+            hook.step(&event(self.path.clone()), node);
+        }
+    }
+}
+
+///
+/// one recorded step: the operation it belongs to, the event, and a copy of the tree after it.
+/// the first step of an operation has no event and holds the tree before it.
+///
+#[derive(Debug, Clone)]
+pub struct TraceStep<T, const B: usize = 4, M: Monoid<T> = ()> {
+    pub operation: String,
+    pub event: Option<TraceEvent>,
+    pub snapshot: BTree<T, B, M>,
+}
+
+///
+/// a [`TraceHook`] which keeps every step with a snapshot of the tree, to be rendered by [`TraceRecorder::to_html`].
+/// it keeps its own copy of the tree, and puts each changed node into it to take the snapshot.
+///
+/// # Example
+/// ```
+/// use algorithm::{Tree234, TraceEvent, TraceRecorder};
+/// let mut tree = Tree234::from(vec![1, 2, 3]);
+/// let mut recorder = TraceRecorder::new();
+/// tree.insert_traced(4, &mut recorder);
+/// let events: Vec<_> = recorder.steps.iter().filter_map(|step| step.event.clone()).collect();
+/// assert_eq!(events[0], TraceEvent::Split { path: vec![] });
+/// assert!(recorder.to_html("insert").contains("<svg"));
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct TraceRecorder<T, const B: usize = 4, M: Monoid<T> = ()> {
+    pub steps: Vec<TraceStep<T, B, M>>,
+    operation: String,
+    tree: BTree<T, B, M>,
+}
+
+impl<T, const B: usize, M: Monoid<T>> Default for TraceRecorder<T, B, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>> TraceRecorder<T, B, M> {
+    pub fn new() -> Self {
+        Self { steps: vec![], operation: String::new(), tree: BTree::new() }
+    }
+}
+
+impl<T: Clone, const B: usize, M: Monoid<T>> TraceHook<T, B, M> for TraceRecorder<T, B, M> {
+    fn begin(&mut self, operation: &str, tree: &BTree<T, B, M>) {
+        self.operation = operation.to_string();
+        self.tree = tree.clone();
+        self.steps.push(TraceStep { operation: self.operation.clone(), event: None, snapshot: tree.clone() });
+    }
+
+    fn step(&mut self, event: &TraceEvent, node: &BTree<T, B, M>) {
+        self.tree.replace_at(event.path(), node.clone());
+        self.steps.push(TraceStep { operation: self.operation.clone(), event: Some(event.clone()), snapshot: self.tree.clone() });
+    }
+}
+
+impl<T: Ord + Display, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// insert value like [`BTree::insert`], reporting each split and the final insertion to hook.
+    /// this runs the same algorithm as `insert`, so the tree ends up exactly as `insert` would leave it.
+    ///
+    pub fn insert_traced<H: TraceHook<T, B, M>>(&mut self, value: T, hook: &mut H) {
+        hook.begin(&format!("insert {value}"), self);
+        self.insert_by(value, &T::cmp, &mut Tracer::on(hook));
+        self.debug_validate(&T::cmp);
+    }
+
+    ///
+    /// delete one occurrence of value like [`BTree::delete`], reporting each rotation, merge, shrink,
+    /// the replacement of an internal value by its predecessor and the final removal to hook.
+    /// this runs the same algorithm as `delete`, so the tree ends up exactly as `delete` would leave it.
+    ///
+    pub fn delete_traced<H: TraceHook<T, B, M>>(&mut self, value: &T, hook: &mut H) -> bool {
+        hook.begin(&format!("delete {value}"), self);
+        let deleted = self.delete_by(value, &T::cmp, &mut Tracer::on(hook));
+        self.debug_validate(&T::cmp);
+        deleted
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>> BTree<T, B, M> {
+    ///
+    /// replace the node at path with node, and recompute the cached values of the nodes above it.
+    ///
+    fn replace_at(&mut self, path: &[usize], node: Self) {
+        match path.split_first() {
+            Some((&pos, rest)) => {
+                self.child_mut(pos).replace_at(rest, node);
+                self.update();
+            }
+            None => *self = node,
+        }
+    }
+}
+
+const KEY_WIDTH: usize = 36;
+const NODE_HEIGHT: usize = 32;
+const LEVEL_HEIGHT: usize = 80;
+const GAP: usize = 16;
+
+impl<T: Display, const B: usize, M: Monoid<T>> TraceRecorder<T, B, M> {
+    ///
+    /// render the recorded steps as a self-contained HTML page with one SVG figure per step
+    /// and buttons to go back and forth. the node changed by each step is highlighted.
+    ///
+    pub fn to_html(&self, title: &str) -> String {
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>{}</title>", escape_html(title)).unwrap();
+        html.push_str(
+            "<style>
+body { font-family: sans-serif; }
+.step { display: none; }
+.step.current { display: block; }
+rect { fill: white; stroke: black; }
+rect.changed { fill: #ffe08a; }
+line { stroke: black; }
+text { font-size: 16px; text-anchor: middle; dominant-baseline: central; }
+</style>
+</head>
+<body>\n",
+        );
+        writeln!(html, "<h1>{}</h1>", escape_html(title)).unwrap();
+        html.push_str("<p><button id=\"prev\">&lt;</button> <span id=\"counter\"></span> <button id=\"next\">&gt;</button></p>\n");
+        for (i, step) in self.steps.iter().enumerate() {
+            let class = if i == 0 { "step current" } else { "step" };
+            let caption = match &step.event {
+                Some(event) => format!("{}: {event}", step.operation),
+                None => step.operation.clone(),
+            };
+            writeln!(html, "<div class=\"{class}\">\n<p>{}</p>", escape_html(&caption)).unwrap();
+            let changed = step.event.as_ref().map(|event| event.path().to_vec());
+            html.push_str(&svg(&step.snapshot, changed.as_deref()));
+            html.push_str("</div>\n");
+        }
+        html.push_str(
+            "<script>
+const steps = document.querySelectorAll('.step');
+let current = 0;
+function show(i) {
+    steps[current].classList.remove('current');
+    current = Math.max(0, Math.min(steps.length - 1, i));
+    steps[current].classList.add('current');
+    document.getElementById('counter').textContent = (current + 1) + ' / ' + steps.length;
+}
+document.getElementById('prev').onclick = () => show(current - 1);
+document.getElementById('next').onclick = () => show(current + 1);
+document.addEventListener('keydown', e => {
+    if (e.key === 'ArrowLeft') show(current - 1);
+    if (e.key === 'ArrowRight') show(current + 1);
+});
+if (steps.length > 0) show(0);
+</script>
+</body>
+</html>\n",
+        );
+        html
+    }
+}
+
+impl TraceEvent {
+    ///
+    /// return the path of the node the event changed.
+    ///
+    pub fn path(&self) -> &[usize] {
+        match self {
+            TraceEvent::Split { path }
+            | TraceEvent::Rotate { path, .. }
+            | TraceEvent::Merge { path, .. }
+            | TraceEvent::Shrink { path }
+            | TraceEvent::SwapWithPredecessor { path, .. }
+            | TraceEvent::Insert { path, .. }
+            | TraceEvent::Remove { path, .. } => path,
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+///
+/// the width of a node box with size values.
+///
+fn node_width(size: usize) -> usize {
+    KEY_WIDTH * size.max(1)
+}
+
+///
+/// draw tree as an SVG figure, laying the leaves out from left to right and centring each node over its children.
+/// the node at changed is highlighted.
+///
+fn svg<T: Display, const B: usize, M: Monoid<T>>(tree: &BTree<T, B, M>, changed: Option<&[usize]>) -> String {
+    let mut body = String::new();
+    let mut path = vec![];
+    let (mut next_x, mut max_depth) = (GAP, 0);
+    if !tree.is_empty() {
+        layout(tree, 0, &mut path, changed, &mut next_x, &mut max_depth, &mut body);
+    }
+    let width = next_x.max(2 * GAP);
+    let height = (max_depth + 1) * LEVEL_HEIGHT;
+    format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n{body}</svg>\n")
+}
+
+///
+/// draw the subtree rooted at node and return the x coordinate of its left edge.
+///
+fn layout<T: Display, const B: usize, M: Monoid<T>>(
+    node: &BTree<T, B, M>,
+    depth: usize,
+    path: &mut Vec<usize>,
+    changed: Option<&[usize]>,
+    next_x: &mut usize,
+    max_depth: &mut usize,
+    body: &mut String,
+) -> usize {
+    *max_depth = (*max_depth).max(depth);
+    let width = node_width(node.size());
+    let y = GAP + depth * LEVEL_HEIGHT;
+    let mut child_x = vec![];
+    for (i, child) in node.children.iter().enumerate() {
+        path.push(i);
+        let x = layout(child, depth + 1, path, changed, next_x, max_depth, body);
+        path.pop();
+        child_x.push(x + node_width(child.size()) / 2);
+    }
+    // place the leaves from left to right, and each internal node at the middle of its children.
+    let x = match (child_x.first(), child_x.last()) {
+        (Some(&first), Some(&last)) => ((first + last) / 2).saturating_sub(width / 2),
+        _ => {
+            let x = *next_x;
+            *next_x += width + GAP;
+            x
+        }
+    };
+    for (i, &cx) in child_x.iter().enumerate() {
+        writeln!(body, "<line x1=\"{}\" y1=\"{}\" x2=\"{cx}\" y2=\"{}\"/>", x + i * KEY_WIDTH, y + NODE_HEIGHT, y + LEVEL_HEIGHT).unwrap();
+    }
+    let class = if changed == Some(path.as_slice()) { " class=\"changed\"" } else { "" };
+    writeln!(body, "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{NODE_HEIGHT}\"{class}/>").unwrap();
+    for (i, value) in node.data.iter().enumerate() {
+        let kx = x + i * KEY_WIDTH;
+        if i > 0 {
+            writeln!(body, "<line x1=\"{kx}\" y1=\"{y}\" x2=\"{kx}\" y2=\"{}\"/>", y + NODE_HEIGHT).unwrap();
+        }
+        let text = escape_html(&value.to_string());
+        writeln!(body, "<text x=\"{}\" y=\"{}\">{text}</text>", kx + KEY_WIDTH / 2, y + NODE_HEIGHT / 2).unwrap();
+    }
+    x
+}
@@ -0,0 +1,210 @@
+use crate::{BTree, Compare, Link, Monoid};
+use std::fmt::Display;
+
+///
+/// a broken invariant of a BTree found by [`BTree::validate`].
+/// depth is 0 at the root, and index is the position of the value or child inside the node.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantError {
+    /// a leaf is not at the same depth as the first leaf.
+    UnevenDepth { expected: usize, found: usize },
+    /// a node has no value or more than B - 1 values.
+    NodeSize { depth: usize, size: usize },
+    /// an internal node has fewer children than its number of values + 1.
+    MissingChild { depth: usize, index: usize },
+    /// an internal node has more children than its number of values + 1.
+    UnexpectedChild { depth: usize, index: usize },
+    /// a value is larger than the next value of the same node.
+    UnsortedNode { depth: usize, index: usize },
+    /// a value lies outside the separators of its parent.
+    OutOfRange { depth: usize, index: usize },
+    /// the cached number of values of a subtree is wrong.
+    LengthMismatch { depth: usize, cached: usize, actual: usize },
+    /// the cached aggregate of a subtree is not the fold of its values. found by [`BTree::validate_aggregate`].
+    AggregateMismatch { depth: usize },
+}
+
+impl Display for InvariantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantError::UnevenDepth { expected, found } => {
+                write!(f, "a leaf is at depth {found} while another leaf is at depth {expected}")
+            }
+            InvariantError::NodeSize { depth, size } => {
+                write!(f, "a node at depth {depth} has {size} values")
+            }
+            InvariantError::MissingChild { depth, index } => {
+                write!(f, "children[{index}] of an internal node at depth {depth} is missing")
+            }
+            InvariantError::UnexpectedChild { depth, index } => {
+                write!(f, "children[{index}] of a node at depth {depth} should be None")
+            }
+            InvariantError::UnsortedNode { depth, index } => {
+                write!(f, "data[{index}] of a node at depth {depth} is larger than data[{}]", index + 1)
+            }
+            InvariantError::OutOfRange { depth, index } => {
+                write!(f, "data[{index}] of a node at depth {depth} is outside the separators of its parent")
+            }
+            InvariantError::LengthMismatch { depth, cached, actual } => {
+                write!(f, "a node at depth {depth} caches {cached} values but its subtree has {actual}")
+            }
+            InvariantError::AggregateMismatch { depth } => {
+                write!(f, "a node at depth {depth} caches an aggregate which is not the fold of its subtree")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+impl<T: Ord, const B: usize, M: Monoid<T>, L: Link> BTree<T, B, M, L> {
+    ///
+    /// check the structural invariants of the tree:
+    /// all leaves are at the same depth, every node except an empty root has 1..=B - 1 values,
+    /// values are sorted inside nodes and lie between the separators of their parent,
+    /// internal nodes have one more child than values, and the cached subtree lengths are right.
+    /// ```rust
+    /// use algorithm::Tree234;
+    /// let tree = Tree234::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.validate(), Ok(()));
+    /// ```
+    ///
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        self.validate_by(&T::cmp)
+    }
+}
+
+impl<T: Ord, const B: usize, M: Monoid<T>, L: Link> BTree<T, B, M, L>
+where
+    M::Value: PartialEq,
+{
+    ///
+    /// check the invariants checked by [`BTree::validate`],
+    /// and that the cached aggregate of every node is the fold of the values in its subtree.
+    /// ```rust
+    /// use algorithm::{BTree, Sum};
+    /// let tree = BTree::<u64, 4, Sum>::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.validate_aggregate(), Ok(()));
+    /// ```
+    ///
+    pub fn validate_aggregate(&self) -> Result<(), InvariantError> {
+        self.validate()?;
+        self.validate_fold(0)?;
+        Ok(())
+    }
+
+    ///
+    /// fold the values of the subtree rooted at self from scratch, checking the cached aggregates on the way.
+    ///
+    fn validate_fold(&self, depth: usize) -> Result<M::Value, InvariantError> {
+        let mut actual = M::identity();
+        for i in 0..=self.size() {
+            if !self.is_leaf() {
+                actual = M::op(&actual, &self.child(i).validate_fold(depth + 1)?);
+            }
+            if i < self.size() {
+                actual = M::op(&actual, &M::single(self.key(i)));
+            }
+        }
+        if self.aggregate != actual {
+            return Err(InvariantError::AggregateMismatch { depth });
+        }
+        Ok(actual)
+    }
+}
+
+impl<T, const B: usize, M: Monoid<T>, L: Link> BTree<T, B, M, L> {
+    ///
+    /// check the invariants of the tree in the order given by cmp.
+    ///
+    pub(crate) fn validate_by<C: Compare<T>>(&self, cmp: &C) -> Result<(), InvariantError> {
+        if self.size() == 0 && self.is_leaf() {
+            // 空の根だけは値を持たなくてよい.
+            return self.validate_length(0, 0);
+        }
+        let mut leaf_depth = None;
+        self.validate_node(0, None, None, &mut leaf_depth, cmp)?;
+        Ok(())
+    }
+
+    ///
+    /// panic if the tree is broken. this does nothing without the `debug-invariants` feature.
+    ///
+    #[inline]
+    pub(crate) fn debug_validate<C: Compare<T>>(&self, cmp: &C) {
+        #[cfg(feature = "debug-invariants")]
+        if let Err(error) = self.validate_by(cmp) {
+            panic!("Tree234 invariant violated: {error}");
+        }
+        #[cfg(not(feature = "debug-invariants"))]
+        let _ = cmp;
+    }
+
+    ///
+    /// check the subtree rooted at self, whose values must lie between lower and upper.
+    /// return the number of values in the subtree.
+    ///
+    fn validate_node<C: Compare<T>>(
+        &self,
+        depth: usize,
+        lower: Option<&T>,
+        upper: Option<&T>,
+        leaf_depth: &mut Option<usize>,
+        cmp: &C,
+    ) -> Result<usize, InvariantError> {
+        let size = self.size();
+        if size == 0 || size >= B {
+            return Err(InvariantError::NodeSize { depth, size });
+        }
+        self.validate_children(depth)?;
+        for i in 0..size {
+            let value = self.key(i);
+            if i + 1 < size && cmp.compare(value, self.key(i + 1)).is_gt() {
+                return Err(InvariantError::UnsortedNode { depth, index: i });
+            }
+            let above_lower = lower.is_none_or(|lower| cmp.compare(lower, value).is_le());
+            let below_upper = upper.is_none_or(|upper| cmp.compare(value, upper).is_le());
+            if !above_lower || !below_upper {
+                return Err(InvariantError::OutOfRange { depth, index: i });
+            }
+        }
+        if self.is_leaf() {
+            let expected = *leaf_depth.get_or_insert(depth);
+            if expected != depth {
+                return Err(InvariantError::UnevenDepth { expected, found: depth });
+            }
+            self.validate_length(depth, size)?;
+            return Ok(size);
+        }
+        let mut actual = size;
+        for i in 0..=size {
+            let lower = if i == 0 { lower } else { Some(self.key(i - 1)) };
+            let upper = if i == size { upper } else { Some(self.key(i)) };
+            actual += self.child(i).validate_node(depth + 1, lower, upper, leaf_depth, cmp)?;
+        }
+        self.validate_length(depth, actual)?;
+        Ok(actual)
+    }
+
+    ///
+    /// check that an internal node has exactly one more child than values.
+    ///
+    fn validate_children(&self, depth: usize) -> Result<(), InvariantError> {
+        let (expected, found) = (self.size() + 1, self.children.len());
+        if !self.is_leaf() && found < expected {
+            return Err(InvariantError::MissingChild { depth, index: found });
+        }
+        if !self.is_leaf() && found > expected {
+            return Err(InvariantError::UnexpectedChild { depth, index: expected });
+        }
+        Ok(())
+    }
+
+    fn validate_length(&self, depth: usize, actual: usize) -> Result<(), InvariantError> {
+        if self.length != actual {
+            return Err(InvariantError::LengthMismatch { depth, cached: self.length, actual });
+        }
+        Ok(())
+    }
+}
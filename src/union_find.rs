@@ -1,11 +1,14 @@
 pub struct UnionFind {
     pub parent: Vec<usize>,
+    /// the number of elements in the set of each root. the values at the other elements are left as they were.
+    pub size: Vec<usize>,
 }
 
 impl UnionFind {
     pub fn new(n: usize) -> Self {
         let parent = (0..n).collect();
-        Self { parent }
+        let size = vec![1; n];
+        Self { parent, size }
     }
 
     fn root(&mut self, x: usize) -> usize {
@@ -21,13 +24,26 @@ impl UnionFind {
         self.root(x) == self.root(y)
     }
 
+    ///
+    /// the number of elements in the set which contains x.
+    ///
+    pub fn size(&mut self, x: usize) -> usize {
+        let x = self.root(x);
+        self.size[x]
+    }
+
+    ///
+    /// unite the sets of x and y, putting the smaller set under the root of the larger one.
+    /// if both have the same size, the root with the smaller index goes under the other.
+    ///
     pub fn unite(&mut self, x: usize, y: usize) {
         let x = self.root(x);
         let y = self.root(y);
-        if x < y {
-            self.parent[x] = y;
-        } else {
-            self.parent[y] = x;
+        if x == y {
+            return;
         }
+        let (child, root) = if (self.size[x], x) < (self.size[y], y) { (x, y) } else { (y, x) };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
     }
-}
\ No newline at end of file
+}